@@ -0,0 +1,167 @@
+//! `#[derive(Scrape)]` for [Rust-Scraper](https://github.com/SergioSaavi/Rust-Scraper).
+//!
+//! Use it through the `rust_scraper` re-export; the generated code refers to
+//! `::rust_scraper` and does not work without it.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitStr, Type};
+
+/// Implement `rust_scraper::Scrape` for a struct with named fields.
+///
+/// Every field takes a `#[scrape(...)]` attribute:
+///
+/// - `selector = "..."`: element to read, relative to the record's root
+///   element; the root element itself when omitted.
+/// - `attr = "..."`: read this attribute instead of the text content.
+/// - `html`: read the inner HTML instead of the text content.
+/// - `regex = "..."`: keep only the first capture group (or whole match).
+/// - `parse = "..."`: `"number"` (or `"price"`, for text such as `"£51.77"`),
+///   `"url"` to resolve against the page URL, or `"date"` for `YYYY-MM-DD`.
+///
+/// `Vec<T>` fields read every matching element, `Option<T>` fields accept a
+/// missing element, and all other field types must implement `FromField`.
+#[proc_macro_derive(Scrape, attributes(scrape))]
+pub fn derive_scrape(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(syn::Error::new(input.span(), "Scrape can only be derived for structs with named fields")),
+        },
+        _ => return Err(syn::Error::new(input.span(), "Scrape can only be derived for structs")),
+    };
+
+    let mut specs = Vec::new();
+    let mut takes = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named fields have names");
+        let name = ident.to_string();
+        let spec = FieldSpec::parse(field)?;
+        let list = is_vec(&field.ty);
+        specs.push(spec.to_tokens(&name, list));
+        takes.push(quote! { #ident: record.take(#name)? });
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::rust_scraper::Scrape for #ident #type_generics #where_clause {
+            fn fields() -> ::std::collections::BTreeMap<::std::string::String, ::rust_scraper::Field> {
+                let mut fields = ::std::collections::BTreeMap::new();
+                #(#specs)*
+                fields
+            }
+
+            fn from_record(
+                mut record: ::rust_scraper::Record,
+            ) -> ::std::result::Result<Self, ::rust_scraper::FieldError> {
+                ::std::result::Result::Ok(Self { #(#takes,)* })
+            }
+        }
+    })
+}
+
+// The contents of one field's #[scrape(...)] attribute
+#[derive(Default)]
+struct FieldSpec {
+    selector: Option<LitStr>,
+    attr: Option<LitStr>,
+    html: bool,
+    regex: Option<LitStr>,
+    parse: Option<TokenStream2>,
+}
+
+impl FieldSpec {
+    fn parse(field: &syn::Field) -> syn::Result<Self> {
+        let mut spec = FieldSpec::default();
+        let mut found = false;
+        for attribute in field.attrs.iter().filter(|attribute| attribute.path().is_ident("scrape")) {
+            found = true;
+            attribute.parse_nested_meta(|meta| {
+                if meta.path.is_ident("selector") {
+                    spec.selector = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("attr") {
+                    spec.attr = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("html") {
+                    spec.html = true;
+                } else if meta.path.is_ident("regex") {
+                    let regex: LitStr = meta.value()?.parse()?;
+                    // Caught here rather than on first use
+                    if let Err(e) = regex::Regex::new(&regex.value()) {
+                        return Err(syn::Error::new(regex.span(), e.to_string()));
+                    }
+                    spec.regex = Some(regex);
+                } else if meta.path.is_ident("parse") {
+                    let parse: LitStr = meta.value()?.parse()?;
+                    let kind = match parse.value().as_str() {
+                        "number" | "price" => quote! { Number },
+                        "url" => quote! { Url },
+                        "date" => quote! { Date },
+                        _ => {
+                            return Err(syn::Error::new(
+                                parse.span(),
+                                "expected parse = \"number\", \"price\", \"url\" or \"date\"",
+                            ));
+                        }
+                    };
+                    spec.parse = Some(kind);
+                } else {
+                    return Err(meta.error("expected selector, attr, html, regex or parse"));
+                }
+                Ok(())
+            })?;
+        }
+        if !found {
+            return Err(syn::Error::new(field.span(), "missing #[scrape(...)] attribute"));
+        }
+        if spec.html && spec.attr.is_some() {
+            return Err(syn::Error::new(field.span(), "`html` and `attr` cannot be combined"));
+        }
+        Ok(spec)
+    }
+
+    // Inserts the field's `rust_scraper::Field` into `fields`
+    fn to_tokens(&self, name: &str, list: bool) -> TokenStream2 {
+        let option = |value: &Option<LitStr>| match value {
+            Some(value) => quote! { ::std::option::Option::Some(::std::string::String::from(#value)) },
+            None => quote! { ::std::option::Option::None },
+        };
+        let (selector, attr, regex) = (option(&self.selector), option(&self.attr), option(&self.regex));
+        let source = if self.html {
+            quote! { ::std::option::Option::Some(::rust_scraper::Source::Html) }
+        } else {
+            quote! { ::std::option::Option::None }
+        };
+        let kind = self.parse.clone().unwrap_or_else(|| quote! { String });
+        quote! {
+            fields.insert(
+                ::std::string::String::from(#name),
+                ::rust_scraper::Field {
+                    selector: #selector,
+                    source: #source,
+                    attr: #attr,
+                    regex: #regex,
+                    kind: ::rust_scraper::FieldType::#kind,
+                    list: #list,
+                    required: false,
+                    fields: ::std::option::Option::None,
+                },
+            );
+        }
+    }
+}
+
+// Whether a field's type is spelled `Vec<...>`
+fn is_vec(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path.qself.is_none() && path.path.segments.last().is_some_and(|segment| segment.ident == "Vec"),
+        _ => false,
+    }
+}
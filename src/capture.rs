@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use async_std::channel::{self, Receiver};
+use base64::Engine;
+use chromiumoxide::cdp::browser_protocol::network::{
+    EventLoadingFailed, EventLoadingFinished, EventResponseReceived, GetResponseBodyParams, RequestId, ResourceType,
+};
+use chromiumoxide::Page;
+use futures::future::{AbortHandle, Abortable};
+use futures::{stream, Stream, StreamExt};
+use serde::de::DeserializeOwned;
+
+use crate::network::header_pairs;
+use crate::scraper::close_page;
+use crate::{Result, ScrapeError, Scraper, UrlPattern};
+
+/// A response a page received, with its body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapturedResponse {
+    pub url: String,
+    pub status: i64,
+    pub headers: Vec<(String, String)>,
+    pub mime_type: String,
+    /// What requested it, such as [`ResourceType::Xhr`] or [`ResourceType::Fetch`].
+    pub resource_type: ResourceType,
+    pub body: Vec<u8>,
+    /// The body parsed as JSON, if it is JSON.
+    pub json: Option<serde_json::Value>,
+}
+
+impl CapturedResponse {
+    /// The value of the header called `name`, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, value)| value.as_str())
+    }
+
+    /// The body as text, replacing invalid UTF-8.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    /// Deserialize the JSON body.
+    pub fn parse<T: DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_slice(&self.body).map_err(|e| ScrapeError::deserialize("capture responses", &self.url, e))
+    }
+}
+
+/// Responses to matching requests, in the order they finish loading.
+///
+/// A [`Stream`] that ends when the page closes. Capturing stops when this
+/// is dropped. Responses whose bodies Chrome has already discarded, such as
+/// those of pages navigated away from, are skipped.
+pub struct ResponseCapture {
+    responses: Receiver<CapturedResponse>,
+    abort: AbortHandle,
+}
+
+enum CaptureEvent {
+    Responded(Arc<EventResponseReceived>),
+    Finished(RequestId),
+    Failed(RequestId),
+}
+
+impl ResponseCapture {
+    /// The responses captured so far, without waiting for more.
+    pub fn drain(&mut self) -> Vec<CapturedResponse> {
+        std::iter::from_fn(|| self.responses.try_recv().ok()).collect()
+    }
+
+    /// Wait until no response has arrived for `quiet`, or the page closed,
+    /// and return every response captured until then.
+    pub async fn collect_until_quiet(&mut self, quiet: Duration) -> Vec<CapturedResponse> {
+        let mut responses = Vec::new();
+        while let Ok(Some(response)) = async_std::future::timeout(quiet, self.responses.next()).await {
+            responses.push(response);
+        }
+        responses
+    }
+}
+
+impl Stream for ResponseCapture {
+    type Item = CapturedResponse;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.responses.poll_next_unpin(cx)
+    }
+}
+
+impl Drop for ResponseCapture {
+    fn drop(&mut self) {
+        self.abort.abort();
+    }
+}
+
+impl Scraper {
+    /// Capture the responses `page` receives from now on for URLs matching
+    /// any of `patterns`, whatever requested them.
+    ///
+    /// ```ignore
+    /// let mut capture = scraper.capture_responses(&page, &[UrlPattern::glob("*/api/products*")]).await?;
+    /// scraper.goto(&page, "https://shop.example/").await?;
+    /// for response in capture.collect_until_quiet(Duration::from_secs(1)).await {
+    ///     let products: Vec<Product> = response.parse()?;
+    /// }
+    /// ```
+    pub async fn capture_responses(&self, page: &Page, patterns: &[UrlPattern]) -> Result<ResponseCapture> {
+        let listen = |e| ScrapeError::cdp("capture responses", "", e);
+        let responded = page.event_listener::<EventResponseReceived>().await.map_err(listen)?;
+        let finished = page.event_listener::<EventLoadingFinished>().await.map_err(listen)?;
+        let failed = page.event_listener::<EventLoadingFailed>().await.map_err(listen)?;
+        let mut events = stream::select_all([
+            responded.map(CaptureEvent::Responded).boxed(),
+            finished.map(|e| CaptureEvent::Finished(e.request_id.clone())).boxed(),
+            failed.map(|e| CaptureEvent::Failed(e.request_id.clone())).boxed(),
+        ]);
+
+        let (sender, responses) = channel::unbounded();
+        let (page, patterns) = (page.clone(), patterns.to_vec());
+        let (abort, registration) = AbortHandle::new_pair();
+        async_std::task::spawn(Abortable::new(
+            async move {
+                // Matching responses waiting for their bodies to finish loading
+                let mut pending = HashMap::new();
+                while let Some(event) = events.next().await {
+                    match event {
+                        CaptureEvent::Responded(event) => {
+                            if patterns.iter().any(|pattern| pattern.matches(&event.response.url)) {
+                                pending.insert(event.request_id.clone(), event);
+                            }
+                        }
+                        CaptureEvent::Failed(id) => {
+                            pending.remove(&id);
+                        }
+                        CaptureEvent::Finished(id) => {
+                            let Some(event) = pending.remove(&id) else {
+                                continue;
+                            };
+                            let Some(response) = read_body(&page, &event).await else {
+                                continue;
+                            };
+                            if sender.send(response).await.is_err() {
+                                break;
+                            }
+                        }
+                    }
+                }
+            },
+            registration,
+        ));
+        Ok(ResponseCapture { responses, abort })
+    }
+
+    /// Open `url` in a new tab, wait for the session's load state and then
+    /// for `quiet` without a new matching response, and return the responses
+    /// captured for `patterns` along the way. The tab is closed before
+    /// returning.
+    pub async fn open_capturing(
+        &self,
+        url: &str,
+        patterns: &[UrlPattern],
+        quiet: Duration,
+    ) -> Result<Vec<CapturedResponse>> {
+        let page = self.new_tab(url).await?;
+        let responses = async {
+            let mut capture = self.capture_responses(&page, patterns).await?;
+            self.goto(&page, url).await?;
+            Ok(capture.collect_until_quiet(quiet).await)
+        }
+        .await;
+        close_page(page, responses).await
+    }
+}
+
+// The response with its body, or None if Chrome no longer has the body
+async fn read_body(page: &Page, event: &EventResponseReceived) -> Option<CapturedResponse> {
+    let returned = page.execute(GetResponseBodyParams::new(event.request_id.clone())).await.ok()?.result;
+    let body = if returned.base64_encoded {
+        base64::engine::general_purpose::STANDARD.decode(&returned.body).ok()?
+    } else {
+        returned.body.into_bytes()
+    };
+    let json = serde_json::from_slice(&body).ok();
+    let response = &event.response;
+    Some(CapturedResponse {
+        url: response.url.clone(),
+        status: response.status,
+        headers: header_pairs(&response.headers),
+        mime_type: response.mime_type.clone(),
+        resource_type: event.r#type.clone(),
+        body,
+        json,
+    })
+}
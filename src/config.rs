@@ -0,0 +1,251 @@
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use chromiumoxide::browser::HeadlessMode;
+use chromiumoxide::BrowserConfig;
+use serde::Deserialize;
+
+use crate::{Result, ScrapeError};
+
+/// Environment variable naming a config file to load when none is given.
+pub const CONFIG_PATH_VAR: &str = "SCRAPER_CONFIG";
+
+/// How the browser window is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BrowserMode {
+    /// Chrome's classic headless mode.
+    #[default]
+    Headless,
+    /// Chrome's new headless mode, which behaves like a regular browser.
+    NewHeadless,
+    /// A visible browser window.
+    Headful,
+}
+
+/// Timeouts applied to browser operations, in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Timeouts {
+    /// How long to wait for the browser process to come up.
+    pub launch_ms: u64,
+    /// How long a single DevTools command may take.
+    pub request_ms: u64,
+    /// How long a page may take to load.
+    pub navigation_ms: u64,
+    /// How long explicit waits for page conditions may take.
+    pub wait_ms: u64,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            launch_ms: 20_000,
+            request_ms: 30_000,
+            navigation_ms: 30_000,
+            wait_ms: 10_000,
+        }
+    }
+}
+
+impl Timeouts {
+    /// [`Timeouts::launch_ms`] as a duration.
+    pub fn launch(&self) -> Duration {
+        Duration::from_millis(self.launch_ms)
+    }
+
+    /// [`Timeouts::request_ms`] as a duration.
+    pub fn request(&self) -> Duration {
+        Duration::from_millis(self.request_ms)
+    }
+
+    /// [`Timeouts::navigation_ms`] as a duration.
+    pub fn navigation(&self) -> Duration {
+        Duration::from_millis(self.navigation_ms)
+    }
+
+    /// [`Timeouts::wait_ms`] as a duration.
+    pub fn wait(&self) -> Duration {
+        Duration::from_millis(self.wait_ms)
+    }
+}
+
+/// Browser launch settings, loadable from a TOML file.
+///
+/// ```toml
+/// [browser]
+/// mode = "new-headless"
+/// window_size = [1280, 800]
+/// executable = "/usr/bin/chromium"
+/// proxy = "http://proxy.internal:3128"
+/// sandbox = false
+///
+/// [timeouts]
+/// navigation_ms = 45000
+/// ```
+///
+/// Every setting can be overridden with a `SCRAPER_*` environment variable;
+/// see [`ScraperConfig::apply_env`].
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScraperConfig {
+    pub browser: BrowserSettings,
+    pub timeouts: Timeouts,
+}
+
+/// The `[browser]` section of a [`ScraperConfig`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BrowserSettings {
+    pub mode: BrowserMode,
+    /// Window size as `[width, height]`. Pages can emulate other screens
+    /// with [`Scraper::emulate_device`](crate::Scraper::emulate_device).
+    pub window_size: (u32, u32),
+    /// Chrome/Chromium binary; auto-detected when unset.
+    pub executable: Option<PathBuf>,
+    /// Profile directory; a throwaway directory is used when unset.
+    pub user_data_dir: Option<PathBuf>,
+    /// Extra command line arguments passed to Chrome.
+    pub args: Vec<String>,
+    /// Proxy server, e.g. `http://host:port` or `socks5://host:port`.
+    pub proxy: Option<String>,
+    /// Run Chrome inside its sandbox. Usually must be disabled in containers.
+    pub sandbox: bool,
+}
+
+impl Default for BrowserSettings {
+    fn default() -> Self {
+        Self {
+            mode: BrowserMode::default(),
+            window_size: (1280, 800), // Fixes mobile viewport
+            executable: None,
+            user_data_dir: None,
+            args: vec!["--disable-blink-features=AutomationControlled".to_string()], // Avoid detection
+            proxy: None,
+            sandbox: true,
+        }
+    }
+}
+
+impl ScraperConfig {
+    /// Load settings from `path` (or from the file named by `SCRAPER_CONFIG`,
+    /// or the defaults when neither is set), then apply environment overrides.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let path = path
+            .map(Path::to_path_buf)
+            .or_else(|| std::env::var_os(CONFIG_PATH_VAR).map(PathBuf::from));
+        let mut config = match path {
+            Some(path) => Self::from_file(&path)?,
+            None => Self::default(),
+        };
+        config.apply_env(std::env::vars_os())?;
+        Ok(config)
+    }
+
+    /// Parse a TOML config file without looking at the environment.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path).map_err(|e| ScrapeError::io("load config", path, e))?;
+        toml::from_str(&contents).map_err(|e| ScrapeError::Config(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Override settings from `SCRAPER_*` variables:
+    ///
+    /// | Variable | Setting |
+    /// |---|---|
+    /// | `SCRAPER_MODE` | `headless`, `new-headless` or `headful` |
+    /// | `SCRAPER_WINDOW_SIZE` | `WIDTHxHEIGHT` |
+    /// | `SCRAPER_CHROME` | executable path |
+    /// | `SCRAPER_USER_DATA_DIR` | profile directory |
+    /// | `SCRAPER_ARGS` | whitespace-separated extra arguments, appended; none may contain a space |
+    /// | `SCRAPER_PROXY` | proxy server |
+    /// | `SCRAPER_SANDBOX` | `true` or `false` |
+    /// | `SCRAPER_LAUNCH_TIMEOUT_MS` | launch timeout |
+    /// | `SCRAPER_REQUEST_TIMEOUT_MS` | request timeout |
+    /// | `SCRAPER_NAVIGATION_TIMEOUT_MS` | navigation timeout |
+    /// | `SCRAPER_WAIT_TIMEOUT_MS` | wait timeout |
+    ///
+    /// Other variables are ignored, even if they are not valid UTF-8; a
+    /// `SCRAPER_*` value that is not is rejected. Arguments containing spaces,
+    /// such as `--user-agent=Mozilla/5.0 (X11)`, go in the config file's
+    /// `args` list instead of `SCRAPER_ARGS`.
+    pub fn apply_env<K, V>(&mut self, vars: impl IntoIterator<Item = (K, V)>) -> Result<()>
+    where
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        for (key, value) in vars {
+            let Some(key) = key.as_ref().to_str().filter(|key| key.starts_with("SCRAPER_")) else {
+                continue;
+            };
+            let Some(value) = value.as_ref().to_str().map(String::from) else {
+                return Err(ScrapeError::Config(format!("{} is not valid UTF-8", key)));
+            };
+            let browser = &mut self.browser;
+            match key {
+                "SCRAPER_MODE" => browser.mode = parse_env(key, &value, parse_mode)?,
+                "SCRAPER_WINDOW_SIZE" => browser.window_size = parse_env(key, &value, parse_window_size)?,
+                "SCRAPER_CHROME" => browser.executable = Some(PathBuf::from(value)),
+                "SCRAPER_USER_DATA_DIR" => browser.user_data_dir = Some(PathBuf::from(value)),
+                "SCRAPER_ARGS" => browser.args.extend(value.split_whitespace().map(String::from)),
+                "SCRAPER_PROXY" => browser.proxy = Some(value),
+                "SCRAPER_SANDBOX" => browser.sandbox = parse_env(key, &value, |v| v.parse().ok())?,
+                "SCRAPER_LAUNCH_TIMEOUT_MS" => self.timeouts.launch_ms = parse_env(key, &value, |v| v.parse().ok())?,
+                "SCRAPER_REQUEST_TIMEOUT_MS" => self.timeouts.request_ms = parse_env(key, &value, |v| v.parse().ok())?,
+                "SCRAPER_NAVIGATION_TIMEOUT_MS" => {
+                    self.timeouts.navigation_ms = parse_env(key, &value, |v| v.parse().ok())?
+                }
+                "SCRAPER_WAIT_TIMEOUT_MS" => self.timeouts.wait_ms = parse_env(key, &value, |v| v.parse().ok())?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Translate the settings into a chromiumoxide [`BrowserConfig`].
+    pub fn browser_config(&self) -> Result<BrowserConfig> {
+        let browser = &self.browser;
+        let (width, height) = browser.window_size;
+        let mut builder = BrowserConfig::builder()
+            .headless_mode(match browser.mode {
+                BrowserMode::Headless => HeadlessMode::True,
+                BrowserMode::NewHeadless => HeadlessMode::New,
+                BrowserMode::Headful => HeadlessMode::False,
+            })
+            .window_size(width, height)
+            .args(browser.args.iter().cloned())
+            .launch_timeout(self.timeouts.launch())
+            .request_timeout(self.timeouts.request());
+        if let Some(executable) = &browser.executable {
+            builder = builder.chrome_executable(executable);
+        }
+        if let Some(dir) = &browser.user_data_dir {
+            builder = builder.user_data_dir(dir);
+        }
+        if let Some(proxy) = &browser.proxy {
+            builder = builder.arg(format!("--proxy-server={}", proxy));
+        }
+        if !browser.sandbox {
+            builder = builder.no_sandbox();
+        }
+        builder.build().map_err(ScrapeError::Config)
+    }
+}
+
+/// Parse a window size such as `1280x800`.
+pub fn parse_window_size(value: &str) -> Option<(u32, u32)> {
+    let (width, height) = value.split_once(['x', 'X'])?;
+    Some((width.trim().parse().ok()?, height.trim().parse().ok()?))
+}
+
+fn parse_mode(value: &str) -> Option<BrowserMode> {
+    match value {
+        "headless" => Some(BrowserMode::Headless),
+        "new-headless" => Some(BrowserMode::NewHeadless),
+        "headful" => Some(BrowserMode::Headful),
+        _ => None,
+    }
+}
+
+fn parse_env<T>(key: &str, value: &str, parse: impl FnOnce(&str) -> Option<T>) -> Result<T> {
+    parse(value.trim()).ok_or_else(|| ScrapeError::Config(format!("invalid value `{}` for {}", value, key)))
+}
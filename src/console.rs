@@ -0,0 +1,376 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use async_std::channel::{self, Receiver};
+use chromiumoxide::cdp::browser_protocol::log::{EventEntryAdded, LogEntryLevel};
+use chromiumoxide::cdp::js_protocol::runtime::{
+    ConsoleApiCalledType, EventConsoleApiCalled, EventExceptionThrown, RemoteObject, StackTrace,
+};
+use chromiumoxide::Page;
+use futures::future::{AbortHandle, Abortable};
+use futures::{stream, StreamExt};
+
+use crate::{Result, ScrapeError, Scraper};
+
+/// How serious a console message is, from least to most.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum ConsoleLevel {
+    /// `console.debug` and verbose browser messages.
+    #[default]
+    Debug,
+    /// `console.log`, `console.info` and the like.
+    Info,
+    Warning,
+    /// `console.error`, failed assertions and uncaught exceptions.
+    Error,
+}
+
+impl FromStr for ConsoleLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "debug" | "verbose" => Ok(ConsoleLevel::Debug),
+            "info" | "log" => Ok(ConsoleLevel::Info),
+            "warning" | "warn" => Ok(ConsoleLevel::Warning),
+            "error" => Ok(ConsoleLevel::Error),
+            _ => Err(format!("unknown console level `{}` (expected debug, info, warning or error)", s)),
+        }
+    }
+}
+
+impl fmt::Display for ConsoleLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConsoleLevel::Debug => f.write_str("debug"),
+            ConsoleLevel::Info => f.write_str("info"),
+            ConsoleLevel::Warning => f.write_str("warning"),
+            ConsoleLevel::Error => f.write_str("error"),
+        }
+    }
+}
+
+/// Where a console message came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConsoleSource {
+    /// A call to the page's `console` API.
+    Console,
+    /// An exception the page's JavaScript did not catch.
+    Exception,
+    /// The browser itself, such as a failed load, a blocked request or a
+    /// deprecation, with Chrome's name for the area: `network`, `security`...
+    Browser(String),
+}
+
+/// A message a page logged, or an error it threw.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConsoleMessage {
+    pub level: ConsoleLevel,
+    pub source: ConsoleSource,
+    /// The logged values separated by spaces, or the exception with its
+    /// stack trace.
+    pub text: String,
+    /// Script or resource the message is about, when known.
+    pub url: Option<String>,
+    /// 1-based line in `url`.
+    pub line: Option<i64>,
+}
+
+impl fmt::Display for ConsoleMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.level, self.text)?;
+        match (&self.url, self.line) {
+            (Some(url), Some(line)) => write!(f, " ({}:{})", url, line),
+            (Some(url), None) => write!(f, " ({})", url),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Called with every message a collector keeps, as it arrives.
+pub type ConsoleCallback = Arc<dyn Fn(&ConsoleMessage) + Send + Sync>;
+
+/// Which console messages to collect, and what to call with them.
+///
+/// ```ignore
+/// let options = ConsoleOptions::default()
+///     .level(ConsoleLevel::Warning)
+///     .on_message(|message| eprintln!("{}", message));
+/// ```
+#[derive(Clone, Default)]
+pub struct ConsoleOptions {
+    /// Least serious level to keep.
+    pub level: ConsoleLevel,
+    pub callback: Option<ConsoleCallback>,
+}
+
+impl ConsoleOptions {
+    pub fn level(mut self, level: ConsoleLevel) -> Self {
+        self.level = level;
+        self
+    }
+
+    pub fn on_message(mut self, callback: impl Fn(&ConsoleMessage) + Send + Sync + 'static) -> Self {
+        self.callback = Some(Arc::new(callback));
+        self
+    }
+}
+
+impl fmt::Debug for ConsoleOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConsoleOptions")
+            .field("level", &self.level)
+            .field("callback", &self.callback.as_ref().map(|_| "Fn(&ConsoleMessage)"))
+            .finish()
+    }
+}
+
+/// The console messages and uncaught errors of a page, collected in the
+/// background as it logs them.
+///
+/// Collecting stops when the page closes or this is dropped.
+pub struct ConsoleCollector {
+    messages: Arc<Mutex<Vec<ConsoleMessage>>>,
+    abort: AbortHandle,
+}
+
+impl ConsoleCollector {
+    /// The messages collected so far, oldest first.
+    pub fn messages(&self) -> Vec<ConsoleMessage> {
+        self.messages.lock().unwrap().clone()
+    }
+
+    /// The messages collected so far, leaving none behind.
+    pub fn take(&self) -> Vec<ConsoleMessage> {
+        std::mem::take(&mut *self.messages.lock().unwrap())
+    }
+
+    /// Whether the page has logged an error or thrown an uncaught exception.
+    pub fn has_errors(&self) -> bool {
+        self.messages.lock().unwrap().iter().any(|message| message.level == ConsoleLevel::Error)
+    }
+}
+
+impl Drop for ConsoleCollector {
+    fn drop(&mut self) {
+        self.abort.abort();
+    }
+}
+
+// Buffers of the `with_console` calls in progress
+type Sinks = Arc<Mutex<Vec<Arc<Mutex<Vec<ConsoleMessage>>>>>>;
+
+// Console collection for every tab the helpers open
+pub(crate) struct ConsoleSession {
+    options: ConsoleOptions,
+    sinks: Sinks,
+    opened: AtomicUsize,
+    // Each tab's number in opening order, and a channel that closes once
+    // its listener has delivered the last of its messages
+    listeners: Mutex<Vec<(usize, Receiver<()>)>>,
+}
+
+// How long `with_console` waits for the tabs an operation opened to
+// deliver their last messages, for tabs the operation left open
+const DRAIN_TIMEOUT: Duration = Duration::from_millis(500);
+
+enum ConsoleEvent {
+    Called(Arc<EventConsoleApiCalled>),
+    Thrown(Arc<EventExceptionThrown>),
+    Logged(Arc<EventEntryAdded>),
+}
+
+impl Scraper {
+    /// Collect the console messages and uncaught exceptions of `page`, and
+    /// the browser's own log entries about it, from now on.
+    pub async fn collect_console(&self, page: &Page, options: &ConsoleOptions) -> Result<ConsoleCollector> {
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        let collected = messages.clone();
+        let abort = listen(page, options, move |message| collected.lock().unwrap().push(message)).await?;
+        Ok(ConsoleCollector { messages, abort })
+    }
+
+    /// The console collection applied to every tab the helpers open, if any.
+    pub fn console(&self) -> Option<&ConsoleOptions> {
+        self.console.as_ref().map(|console| &console.options)
+    }
+
+    /// Collect console messages from every tab the helpers open from now
+    /// on, including pooled and crawled ones, or stop with None. Messages
+    /// go to the options' callback, and to [`Scraper::with_console`].
+    pub fn set_console(&mut self, options: Option<ConsoleOptions>) {
+        self.console = options.map(|options| ConsoleSession {
+            options,
+            sinks: Sinks::default(),
+            opened: AtomicUsize::new(0),
+            listeners: Mutex::default(),
+        });
+    }
+
+    /// Run `operation` and return its result with the console messages
+    /// tabs logged while it ran, so a scrape that came back empty can be
+    /// told apart from one whose page crashed. Messages are only collected
+    /// once [`Scraper::set_console`] is on; operations running at the same
+    /// time share them. Messages still queued for tabs the operation opened
+    /// are waited for, briefly for tabs it left open.
+    ///
+    /// ```ignore
+    /// scraper.set_console(Some(ConsoleOptions::default().level(ConsoleLevel::Error)));
+    /// let (books, console) = scraper.with_console(BooksToScrape::new(&scraper).catalogue()).await;
+    /// ```
+    pub async fn with_console<T>(
+        &self,
+        operation: impl Future<Output = Result<T>>,
+    ) -> (Result<T>, Vec<ConsoleMessage>) {
+        let Some(console) = &self.console else {
+            return (operation.await, Vec::new());
+        };
+        let sink = Arc::new(Mutex::new(Vec::new()));
+        console.sinks.lock().unwrap().push(sink.clone());
+        let first = console.opened.load(Ordering::Relaxed);
+        let result = operation.await;
+
+        // A closed tab's listener ends once it has delivered what was queued
+        let opened: Vec<Receiver<()>> = console
+            .listeners
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(number, _)| *number >= first)
+            .map(|(_, done)| done.clone())
+            .collect();
+        let drained = futures::future::join_all(opened.iter().map(|done| done.recv()));
+        let _ = async_std::future::timeout(DRAIN_TIMEOUT, drained).await;
+        console.sinks.lock().unwrap().retain(|other| !Arc::ptr_eq(other, &sink));
+        let messages = std::mem::take(&mut *sink.lock().unwrap());
+        (result, messages)
+    }
+
+    // Collect the console of `page` for the session
+    pub(crate) async fn collect_console_session(&self, page: &Page) -> Result<()> {
+        let Some(console) = &self.console else {
+            return Ok(());
+        };
+        let sinks = console.sinks.clone();
+        // Dropped with the listener, which closes `done`
+        let (finished, done) = channel::bounded::<()>(1);
+        // Not aborted: collecting ends with the page
+        listen(page, &console.options, move |message| {
+            let _listening = &finished;
+            for sink in sinks.lock().unwrap().iter() {
+                sink.lock().unwrap().push(message.clone());
+            }
+        })
+        .await?;
+        let number = console.opened.fetch_add(1, Ordering::Relaxed);
+        let mut listeners = console.listeners.lock().unwrap();
+        listeners.retain(|(_, done)| !done.is_closed());
+        listeners.push((number, done));
+        Ok(())
+    }
+}
+
+// Pass the messages of `page` that `options` keep to its callback and then
+// to `deliver`, until the page closes or the returned handle aborts
+async fn listen(
+    page: &Page,
+    options: &ConsoleOptions,
+    deliver: impl Fn(ConsoleMessage) + Send + 'static,
+) -> Result<AbortHandle> {
+    let listen = |e| ScrapeError::cdp("collect console", "", e);
+    let called = page.event_listener::<EventConsoleApiCalled>().await.map_err(listen)?;
+    let thrown = page.event_listener::<EventExceptionThrown>().await.map_err(listen)?;
+    let logged = page.event_listener::<EventEntryAdded>().await.map_err(listen)?;
+    let mut events = stream::select_all([
+        called.map(ConsoleEvent::Called).boxed(),
+        thrown.map(ConsoleEvent::Thrown).boxed(),
+        logged.map(ConsoleEvent::Logged).boxed(),
+    ]);
+
+    let options = options.clone();
+    let (abort, registration) = AbortHandle::new_pair();
+    async_std::task::spawn(Abortable::new(
+        async move {
+            while let Some(event) = events.next().await {
+                let message = console_message(&event);
+                if message.level < options.level {
+                    continue;
+                }
+                if let Some(callback) = &options.callback {
+                    callback(&message);
+                }
+                deliver(message);
+            }
+        },
+        registration,
+    ));
+    Ok(abort)
+}
+
+fn console_message(event: &ConsoleEvent) -> ConsoleMessage {
+    match event {
+        ConsoleEvent::Called(event) => {
+            let level = match event.r#type {
+                ConsoleApiCalledType::Debug => ConsoleLevel::Debug,
+                ConsoleApiCalledType::Warning => ConsoleLevel::Warning,
+                ConsoleApiCalledType::Error | ConsoleApiCalledType::Assert => ConsoleLevel::Error,
+                _ => ConsoleLevel::Info,
+            };
+            let text: Vec<String> = event.args.iter().map(describe).collect();
+            let (url, line) = location(event.stack_trace.as_ref());
+            ConsoleMessage { level, source: ConsoleSource::Console, text: text.join(" "), url, line }
+        }
+        ConsoleEvent::Thrown(event) => {
+            let details = &event.exception_details;
+            let text = match details.exception.as_ref().and_then(|exception| exception.description.clone()) {
+                Some(description) => format!("{} {}", details.text, description),
+                None => details.text.clone(),
+            };
+            let (url, line) = match &details.url {
+                Some(url) => (Some(url.clone()), Some(details.line_number + 1)),
+                None => location(details.stack_trace.as_ref()),
+            };
+            ConsoleMessage { level: ConsoleLevel::Error, source: ConsoleSource::Exception, text, url, line }
+        }
+        ConsoleEvent::Logged(event) => {
+            let entry = &event.entry;
+            let level = match entry.level {
+                LogEntryLevel::Verbose => ConsoleLevel::Debug,
+                LogEntryLevel::Info => ConsoleLevel::Info,
+                LogEntryLevel::Warning => ConsoleLevel::Warning,
+                LogEntryLevel::Error => ConsoleLevel::Error,
+            };
+            ConsoleMessage {
+                level,
+                source: ConsoleSource::Browser(entry.source.as_ref().to_string()),
+                text: entry.text.clone(),
+                url: entry.url.clone(),
+                line: entry.line_number.map(|line| line + 1),
+            }
+        }
+    }
+}
+
+// A logged value as the console shows it: strings bare, the rest as JSON
+// or as the browser describes them
+fn describe(value: &RemoteObject) -> String {
+    match &value.value {
+        Some(serde_json::Value::String(text)) => text.clone(),
+        Some(value) => value.to_string(),
+        None => match (&value.unserializable_value, &value.description) {
+            (Some(unserializable), _) => unserializable.inner().clone(),
+            (None, Some(description)) => description.clone(),
+            (None, None) => value.r#type.as_ref().to_string(),
+        },
+    }
+}
+
+// Where the innermost frame of `stack` is, with a 1-based line
+fn location(stack: Option<&StackTrace>) -> (Option<String>, Option<i64>) {
+    match stack.and_then(|stack| stack.call_frames.first()) {
+        Some(frame) if !frame.url.is_empty() => (Some(frame.url.clone()), Some(frame.line_number + 1)),
+        _ => (None, None),
+    }
+}
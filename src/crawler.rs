@@ -0,0 +1,275 @@
+use std::collections::HashSet;
+use chromiumoxide::Page;
+use futures::StreamExt;
+use regex::Regex;
+use url::Url;
+
+use crate::scraper::js_string;
+use crate::{Navigation, PoolOptions, Result, ScrapeError, Scraper};
+
+/// Which links a [`Crawler`] follows and how far.
+#[derive(Debug, Clone)]
+pub struct CrawlOptions {
+    /// Links further than this many hops from a seed are not followed.
+    /// Seeds are at depth 0.
+    pub max_depth: usize,
+    /// Only follow links to hosts of the seed URLs.
+    pub same_domain: bool,
+    /// When non-empty, only follow URLs matching at least one pattern.
+    pub include: Vec<Regex>,
+    /// Never follow URLs matching any of these patterns.
+    pub exclude: Vec<Regex>,
+    /// Stop after visiting this many pages.
+    pub max_pages: Option<usize>,
+    /// Selector of the elements whose `href` is followed, as the browser
+    /// resolves it against the page and any `<base href>`.
+    pub link_selector: String,
+    /// Number of pages crawled in parallel.
+    pub concurrency: usize,
+}
+
+impl Default for CrawlOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 2,
+            same_domain: true,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            max_pages: None,
+            link_selector: "a[href]".to_string(),
+            concurrency: 4,
+        }
+    }
+}
+
+/// A page reached by the crawl, handed to the extraction callback.
+#[derive(Debug, Clone)]
+pub struct CrawledPage {
+    /// Normalized URL that was requested.
+    pub url: String,
+    /// Hops from the seed URL.
+    pub depth: usize,
+    /// Final URL, status and redirects of the load.
+    pub navigation: Navigation,
+}
+
+/// What the crawl produced for one URL.
+#[derive(Debug)]
+pub struct CrawlOutput<T> {
+    pub url: String,
+    pub depth: usize,
+    /// The extraction callback's value, or why the page could not be
+    /// loaded or extracted.
+    pub result: Result<T>,
+}
+
+// The final URL of a visited page, the links found on it and the
+// callback's value for it
+type Visit<T> = Result<(String, Vec<String>, T)>;
+
+/// Breadth-first crawler following links from seed URLs.
+///
+/// URLs are normalized before deduplication (fragment dropped, query
+/// parameters sorted), so each page is visited at most once.
+pub struct Crawler<'a> {
+    scraper: &'a Scraper,
+    options: CrawlOptions,
+}
+
+impl<'a> Crawler<'a> {
+    pub fn new(scraper: &'a Scraper, options: CrawlOptions) -> Self {
+        Self { scraper, options }
+    }
+
+    /// Crawl from `seeds`, calling `extract` on every page visited.
+    ///
+    /// A page that fails to load or extract is reported in its
+    /// [`CrawlOutput`] and does not stop the crawl; its links are not
+    /// followed. The crawl only fails if the browser itself does.
+    pub async fn run<F, Fut, T>(&self, seeds: &[&str], extract: F) -> Result<Vec<CrawlOutput<T>>>
+    where
+        F: Fn(Page, CrawledPage) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut seen = HashSet::new();
+        let mut hosts = HashSet::new();
+        let mut frontier = Vec::new();
+        for seed in seeds {
+            let url = normalize_url(seed)
+                .ok_or_else(|| ScrapeError::Config(format!("invalid seed URL `{}`", seed)))?;
+            if let Some(host) = url.host_str() {
+                hosts.insert(host.to_string());
+            }
+            if seen.insert(url.to_string()) {
+                frontier.push(url.to_string());
+            }
+        }
+
+        let pool = self.scraper.pool(PoolOptions { size: self.options.concurrency, ..PoolOptions::default() });
+        let mut outputs = Vec::new();
+        let mut depth = 0;
+        while !frontier.is_empty() {
+            if let Some(max_pages) = self.options.max_pages {
+                frontier.truncate(max_pages.saturating_sub(outputs.len()));
+            }
+            let extract = &extract;
+            let tasks = frontier.drain(..).map(|url| {
+                move |page: Page| async move {
+                    let visit = async {
+                        let navigation = self.scraper.goto(&page, &url).await?;
+                        let links = if depth < self.options.max_depth {
+                            self.scraper.evaluate(&page, &links_script(&self.options.link_selector)).await?
+                        } else {
+                            Vec::new()
+                        };
+                        let final_url = navigation.url.clone();
+                        let crawled = CrawledPage { url: url.clone(), depth, navigation };
+                        Ok((final_url, links, extract(page.clone(), crawled).await?))
+                    };
+                    // Page failures are reported per URL; only pool errors end the crawl
+                    Ok((url.clone(), visit.await))
+                }
+            });
+            let results: Vec<Result<(String, Visit<T>)>> = pool.run(tasks).collect().await;
+
+            for result in results {
+                let (url, visit) = result?;
+                let result = match visit {
+                    Ok((final_url, links, value)) => {
+                        // A redirect target counts as visited too
+                        if let Some(final_url) = normalize_url(&final_url) {
+                            seen.insert(final_url.to_string());
+                        }
+                        // Relative links resolve against where the page ended up
+                        let base = Url::parse(&final_url).or_else(|_| Url::parse(&url)).ok();
+                        for link in links {
+                            if let Some(base) = &base
+                                && let Some(link) = follow(&self.options, base, &link, &hosts)
+                                && seen.insert(link.clone())
+                            {
+                                frontier.push(link);
+                            }
+                        }
+                        Ok(value)
+                    }
+                    Err(e) => Err(e),
+                };
+                outputs.push(CrawlOutput { url, depth, result });
+            }
+            depth += 1;
+        }
+        pool.close().await;
+        Ok(outputs)
+    }
+}
+
+// The normalized form of `href` found on a page at `base`, if `options`
+// follow it from a crawl of `hosts`
+fn follow(options: &CrawlOptions, base: &Url, href: &str, hosts: &HashSet<String>) -> Option<String> {
+    let url = normalize_url(base.join(href).ok()?.as_str())?;
+    let text = url.as_str();
+    if options.same_domain && !url.host_str().is_some_and(|host| hosts.contains(host)) {
+        return None;
+    }
+    if !options.include.is_empty() && !options.include.iter().any(|re| re.is_match(text)) {
+        return None;
+    }
+    if options.exclude.iter().any(|re| re.is_match(text)) {
+        return None;
+    }
+    Some(text.to_string())
+}
+
+// The resolved `href` of every element matching `selector`
+fn links_script(selector: &str) -> String {
+    format!(
+        "Array.from(document.querySelectorAll({})).map(link => link.href)\
+         .filter(href => typeof href === 'string' && href)",
+        js_string(selector)
+    )
+}
+
+impl Scraper {
+    /// A crawler that opens its pages in this session's browser.
+    pub fn crawler(&self, options: CrawlOptions) -> Crawler<'_> {
+        Crawler::new(self, options)
+    }
+}
+
+/// Normalize an http(s) URL for deduplication: drop the fragment and sort
+/// query parameters. Host case and default ports are normalized by parsing.
+/// Returns `None` for other schemes and unparsable input.
+pub fn normalize_url(input: &str) -> Option<Url> {
+    let mut url = Url::parse(input).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    url.set_fragment(None);
+    let mut pairs: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+    if pairs.is_empty() {
+        url.set_query(None);
+    } else {
+        pairs.sort();
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    Some(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalized(input: &str) -> Option<String> {
+        normalize_url(input).map(String::from)
+    }
+
+    fn hosts(hosts: &[&str]) -> HashSet<String> {
+        hosts.iter().map(|host| host.to_string()).collect()
+    }
+
+    #[test]
+    fn normalizing_drops_fragments_and_sorts_queries() {
+        assert_eq!(normalized("https://a.example/b#top").as_deref(), Some("https://a.example/b"));
+        assert_eq!(normalized("https://a.example/b?#").as_deref(), Some("https://a.example/b"));
+        assert_eq!(normalized("https://a.example/b?z=1&a=2&a=1").as_deref(), Some("https://a.example/b?a=1&a=2&z=1"));
+        assert_eq!(normalized("HTTP://A.example:80/b").as_deref(), Some("http://a.example/b"));
+        assert_eq!(normalized("mailto:someone@a.example"), None);
+        assert_eq!(normalized("not a url"), None);
+    }
+
+    #[test]
+    fn normalizing_keeps_trailing_slashes_on_paths() {
+        assert_eq!(normalized("https://a.example").as_deref(), Some("https://a.example/"));
+        assert_ne!(normalized("https://a.example/docs/"), normalized("https://a.example/docs"));
+    }
+
+    #[test]
+    fn follows_links_relative_to_the_final_url() {
+        let options = CrawlOptions::default();
+        let base = Url::parse("https://a.example/docs/intro").unwrap();
+        let hosts = hosts(&["a.example"]);
+        let follow = |href| follow(&options, &base, href, &hosts);
+        assert_eq!(follow("next#part").as_deref(), Some("https://a.example/docs/next"));
+        assert_eq!(follow("/about").as_deref(), Some("https://a.example/about"));
+        assert_eq!(follow("https://b.example/"), None);
+        assert_eq!(follow("javascript:void(0)"), None);
+    }
+
+    #[test]
+    fn filters_links_by_host_and_pattern() {
+        let base = Url::parse("https://a.example/").unwrap();
+        let seeds = hosts(&["a.example"]);
+
+        let options = CrawlOptions { same_domain: false, ..CrawlOptions::default() };
+        assert_eq!(follow(&options, &base, "https://b.example/x", &seeds).as_deref(), Some("https://b.example/x"));
+
+        let options = CrawlOptions {
+            include: vec![Regex::new("/docs/").unwrap()],
+            exclude: vec![Regex::new("draft").unwrap()],
+            ..CrawlOptions::default()
+        };
+        assert_eq!(follow(&options, &base, "/docs/a", &seeds).as_deref(), Some("https://a.example/docs/a"));
+        assert_eq!(follow(&options, &base, "/blog/a", &seeds), None);
+        assert_eq!(follow(&options, &base, "/docs/draft-a", &seeds), None);
+    }
+}
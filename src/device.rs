@@ -0,0 +1,257 @@
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use chromiumoxide::cdp::browser_protocol::emulation::{
+    SetDeviceMetricsOverrideParams, SetTouchEmulationEnabledParams, SetUserAgentOverrideParams,
+};
+use chromiumoxide::Page;
+use image::imageops::{self, FilterType};
+use image::{Rgba, RgbaImage};
+
+use crate::scraper::{close_page, page_url};
+use crate::{Result, ScrapeError, Scraper, ScreenshotOptions, WaitFor};
+
+/// A device to emulate in a page: its viewport, pixel density, browser and
+/// input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Device {
+    pub name: String,
+    /// Viewport size in CSS pixels.
+    pub width: u32,
+    pub height: u32,
+    pub device_scale_factor: f64,
+    /// User agent to report; the browser's own when unset.
+    pub user_agent: Option<String>,
+    /// Lay pages out as a mobile browser does, honoring `<meta name="viewport">`.
+    pub mobile: bool,
+    /// Report a touch screen instead of a mouse.
+    pub touch: bool,
+}
+
+const IOS_SAFARI: &str = "AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1";
+
+impl Device {
+    /// A desktop browser window of the given viewport size.
+    pub fn desktop(name: impl Into<String>, width: u32, height: u32) -> Self {
+        Self {
+            name: name.into(),
+            width,
+            height,
+            device_scale_factor: 1.,
+            user_agent: None,
+            mobile: false,
+            touch: false,
+        }
+    }
+
+    pub fn iphone_15() -> Self {
+        Self {
+            name: "iPhone 15".to_string(),
+            width: 393,
+            height: 852,
+            device_scale_factor: 3.,
+            user_agent: Some(format!("Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) {}", IOS_SAFARI)),
+            mobile: true,
+            touch: true,
+        }
+    }
+
+    pub fn pixel_8() -> Self {
+        Self {
+            name: "Pixel 8".to_string(),
+            width: 412,
+            height: 915,
+            device_scale_factor: 2.625,
+            user_agent: Some(
+                "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 (KHTML, like Gecko) \
+                 Chrome/120.0.0.0 Mobile Safari/537.36"
+                    .to_string(),
+            ),
+            mobile: true,
+            touch: true,
+        }
+    }
+
+    pub fn ipad_air() -> Self {
+        Self {
+            name: "iPad Air".to_string(),
+            width: 820,
+            height: 1180,
+            device_scale_factor: 2.,
+            user_agent: Some(format!("Mozilla/5.0 (iPad; CPU OS 17_0 like Mac OS X) {}", IOS_SAFARI)),
+            mobile: true,
+            touch: true,
+        }
+    }
+
+    pub fn desktop_1080p() -> Self {
+        Self::desktop("Desktop 1080p", 1920, 1080)
+    }
+
+    /// Every built-in preset, from the smallest screen up.
+    pub fn presets() -> Vec<Device> {
+        vec![Self::iphone_15(), Self::pixel_8(), Self::ipad_air(), Self::desktop_1080p()]
+    }
+
+    /// The built-in preset called `name`, ignoring case, spaces and dashes:
+    /// `iphone-15` finds "iPhone 15".
+    pub fn preset(name: &str) -> Option<Device> {
+        Self::presets().into_iter().find(|device| device.slug() == slug(name))
+    }
+
+    /// The name in lowercase with dashes, for file names: `ipad-air`.
+    pub fn slug(&self) -> String {
+        slug(&self.name)
+    }
+}
+
+fn slug(name: &str) -> String {
+    let words: Vec<String> = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect();
+    words.join("-")
+}
+
+/// A capture of a page on one device.
+#[derive(Debug, Clone)]
+pub struct DeviceCapture {
+    pub device: Device,
+    /// The encoded image, in the format the capture was taken in.
+    pub image: Vec<u8>,
+}
+
+/// Where [`Scraper::take_device_screenshots`] wrote its images.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceScreenshots {
+    /// One capture per device, in the order given.
+    pub files: Vec<PathBuf>,
+    pub contact_sheet: PathBuf,
+}
+
+impl Scraper {
+    /// Emulate `device` in `page`. Navigate afterwards: the user agent and
+    /// touch support are read by pages as they load.
+    pub async fn emulate_device(&self, page: &Page, device: &Device) -> Result<()> {
+        let metrics = SetDeviceMetricsOverrideParams::new(
+            device.width,
+            device.height,
+            device.device_scale_factor,
+            device.mobile,
+        );
+        let mut touch = SetTouchEmulationEnabledParams::new(device.touch);
+        touch.max_touch_points = device.touch.then_some(5);
+        let result = async {
+            page.execute(metrics).await?;
+            page.execute(touch).await?;
+            if let Some(user_agent) = &device.user_agent {
+                page.execute(SetUserAgentOverrideParams::new(user_agent.clone())).await?;
+            }
+            Ok(())
+        }
+        .await;
+        match result {
+            Ok(()) => Ok(()),
+            Err(e) => Err(ScrapeError::cdp("emulate device", page_url(page).await, e)),
+        }
+    }
+
+    /// Open `url` once per device, wait for `ready` to hold, and capture it
+    /// as `options` describe. Devices are captured one after another.
+    pub async fn capture_devices(
+        &self,
+        url: &str,
+        devices: &[Device],
+        ready: &WaitFor,
+        options: &ScreenshotOptions,
+    ) -> Result<Vec<DeviceCapture>> {
+        let mut captures = Vec::with_capacity(devices.len());
+        for device in devices {
+            let page = self.new_tab(url).await?;
+            let image = self.capture_device(&page, url, device, ready, options).await;
+            let image = close_page(page, image).await?;
+            captures.push(DeviceCapture { device: device.clone(), image });
+        }
+        Ok(captures)
+    }
+
+    async fn capture_device(
+        &self,
+        page: &Page,
+        url: &str,
+        device: &Device,
+        ready: &WaitFor,
+        options: &ScreenshotOptions,
+    ) -> Result<Vec<u8>> {
+        self.emulate_device(page, device).await?;
+        self.goto(page, url).await?;
+        self.wait(page, ready).await?;
+        self.screenshot(page, options).await
+    }
+
+    /// Capture `url` on every device as with [`Scraper::capture_devices`],
+    /// writing each image to `dir` under the device's
+    /// [slug](Device::slug), plus a `contact-sheet.png` of them side by side.
+    pub async fn take_device_screenshots(
+        &self,
+        url: &str,
+        devices: &[Device],
+        dir: &Path,
+        ready: &WaitFor,
+        options: &ScreenshotOptions,
+    ) -> Result<DeviceScreenshots> {
+        let captures = self.capture_devices(url, devices, ready, options).await?;
+        std::fs::create_dir_all(dir).map_err(|e| ScrapeError::io("device screenshots", dir, e))?;
+        let mut files = Vec::with_capacity(captures.len());
+        for capture in &captures {
+            let path = dir.join(format!("{}.{}", capture.device.slug(), options.format.extension()));
+            std::fs::write(&path, &capture.image).map_err(|e| ScrapeError::io("device screenshots", &path, e))?;
+            files.push(path);
+        }
+        let contact_sheet = dir.join("contact-sheet.png");
+        let sheet = contact_sheet_png(&captures, CONTACT_SHEET_HEIGHT)?;
+        std::fs::write(&contact_sheet, sheet).map_err(|e| ScrapeError::io("device screenshots", &contact_sheet, e))?;
+        Ok(DeviceScreenshots { files, contact_sheet })
+    }
+}
+
+// Height of each capture on a contact sheet, in pixels
+const CONTACT_SHEET_HEIGHT: u32 = 800;
+const GAP: u32 = 24;
+const BACKGROUND: Rgba<u8> = Rgba([236, 236, 236, 255]);
+
+/// Lay `captures` out left to right, each scaled to `height` pixels tall,
+/// and encode the sheet as PNG.
+pub fn contact_sheet_png(captures: &[DeviceCapture], height: u32) -> Result<Vec<u8>> {
+    let mut thumbnails = Vec::with_capacity(captures.len());
+    for capture in captures {
+        let image = match image::load_from_memory(&capture.image) {
+            Ok(image) => image.to_rgba8(),
+            Err(e) => {
+                return Err(ScrapeError::Image {
+                    step: "contact sheet",
+                    what: capture.device.name.clone(),
+                    message: e.to_string(),
+                });
+            }
+        };
+        // Full-page captures can be far taller than the viewport; keep the top
+        let visible = (image.width() as f64 * capture.device.height as f64 / capture.device.width as f64) as u32;
+        let image = imageops::crop_imm(&image, 0, 0, image.width(), visible.clamp(1, image.height())).to_image();
+        let width = (image.width() as f64 * height as f64 / image.height() as f64).round().max(1.) as u32;
+        thumbnails.push(imageops::resize(&image, width, height, FilterType::Triangle));
+    }
+
+    let width = thumbnails.iter().map(|thumbnail| thumbnail.width() + GAP).sum::<u32>() + GAP;
+    let mut sheet = RgbaImage::from_pixel(width, height + 2 * GAP, BACKGROUND);
+    let mut x = GAP;
+    for thumbnail in &thumbnails {
+        imageops::overlay(&mut sheet, thumbnail, i64::from(x), i64::from(GAP));
+        x += thumbnail.width() + GAP;
+    }
+    let mut png = Vec::new();
+    match sheet.write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png) {
+        Ok(()) => Ok(png),
+        Err(e) => Err(ScrapeError::Image { step: "contact sheet", what: "the sheet".to_string(), message: e.to_string() }),
+    }
+}
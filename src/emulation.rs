@@ -0,0 +1,238 @@
+use std::fmt;
+use std::str::FromStr;
+use chromiumoxide::cdp::browser_protocol::browser::{GrantPermissionsParams, PermissionType};
+use chromiumoxide::cdp::browser_protocol::emulation::{
+    MediaFeature, SetCpuThrottlingRateParams, SetEmulatedMediaParams, SetGeolocationOverrideParams,
+    SetLocaleOverrideParams, SetTimezoneOverrideParams, SetUserAgentOverrideParams,
+};
+use chromiumoxide::Page;
+
+use crate::scraper::page_url;
+use crate::{Device, Result, ScrapeError, Scraper};
+
+/// The color scheme pages see through `prefers-color-scheme`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+
+impl FromStr for ColorScheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "light" => Ok(ColorScheme::Light),
+            "dark" => Ok(ColorScheme::Dark),
+            _ => Err(format!("unknown color scheme `{}` (expected light or dark)", s)),
+        }
+    }
+}
+
+impl fmt::Display for ColorScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorScheme::Light => f.write_str("light"),
+            ColorScheme::Dark => f.write_str("dark"),
+        }
+    }
+}
+
+/// A position to report through the geolocation API.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Geolocation {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Radius of uncertainty, in meters.
+    pub accuracy: f64,
+}
+
+impl Geolocation {
+    pub fn new(latitude: f64, longitude: f64) -> Self {
+        Self { latitude, longitude, accuracy: 10. }
+    }
+}
+
+impl FromStr for Geolocation {
+    type Err = String;
+
+    /// `LATITUDE,LONGITUDE` or `LATITUDE,LONGITUDE,ACCURACY`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let numbers: Option<Vec<f64>> = s.split(',').map(|part| part.trim().parse().ok()).collect();
+        match numbers.as_deref() {
+            Some(&[latitude, longitude]) => Ok(Geolocation::new(latitude, longitude)),
+            Some(&[latitude, longitude, accuracy]) => Ok(Geolocation { latitude, longitude, accuracy }),
+            _ => Err(format!("expected LATITUDE,LONGITUDE[,ACCURACY], got `{}`", s)),
+        }
+    }
+}
+
+/// Who a page thinks is visiting: the device, browser, language, place and
+/// preferences it sees instead of the host machine's. Unset fields keep the
+/// browser's defaults.
+///
+/// ```ignore
+/// let paris = Emulation::default()
+///     .locale("fr-FR")
+///     .timezone("Europe/Paris")
+///     .geolocation(Geolocation::new(48.8566, 2.3522));
+/// let page = scraper.open_emulated("https://shop.example/prices", &paris).await?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Emulation {
+    /// Viewport, pixel density, touch and user agent of a device.
+    pub device: Option<Device>,
+    /// Takes precedence over the device's user agent.
+    pub user_agent: Option<String>,
+    /// Value of the `Accept-Language` header and `navigator.languages`,
+    /// such as `fr-FR,fr;q=0.9`. Defaults to the locale when that is set.
+    pub accept_language: Option<String>,
+    /// ICU locale for `Intl` formatting, such as `fr-FR`.
+    pub locale: Option<String>,
+    /// IANA time zone, such as `Europe/Paris`.
+    pub timezone: Option<String>,
+    /// Position to report, with the geolocation permission granted.
+    pub geolocation: Option<Geolocation>,
+    pub color_scheme: Option<ColorScheme>,
+    /// Match `prefers-reduced-motion: reduce`.
+    pub reduced_motion: bool,
+    /// Slow the CPU down by this factor, e.g. 4 for a low-end phone.
+    pub cpu_throttling: Option<f64>,
+}
+
+impl Emulation {
+    pub fn device(mut self, device: Device) -> Self {
+        self.device = Some(device);
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    pub fn accept_language(mut self, accept_language: impl Into<String>) -> Self {
+        self.accept_language = Some(accept_language.into());
+        self
+    }
+
+    pub fn locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    pub fn timezone(mut self, timezone: impl Into<String>) -> Self {
+        self.timezone = Some(timezone.into());
+        self
+    }
+
+    pub fn geolocation(mut self, geolocation: Geolocation) -> Self {
+        self.geolocation = Some(geolocation);
+        self
+    }
+
+    pub fn color_scheme(mut self, color_scheme: ColorScheme) -> Self {
+        self.color_scheme = Some(color_scheme);
+        self
+    }
+
+    pub fn reduced_motion(mut self) -> Self {
+        self.reduced_motion = true;
+        self
+    }
+
+    pub fn cpu_throttling(mut self, rate: f64) -> Self {
+        self.cpu_throttling = Some(rate);
+        self
+    }
+}
+
+impl Scraper {
+    /// The emulation applied to every tab the helpers open, if any.
+    pub fn emulation(&self) -> Option<&Emulation> {
+        self.emulation.as_ref()
+    }
+
+    /// Emulate `emulation` in every tab the helpers open from now on,
+    /// including pooled and crawled ones, or stop with None.
+    pub fn set_emulation(&mut self, emulation: Option<Emulation>) {
+        self.emulation = emulation;
+    }
+
+    /// Apply `emulation` to `page`. Navigate afterwards: most settings are
+    /// read by pages as they load.
+    ///
+    /// The geolocation permission is granted browser-wide, as Chrome grants
+    /// permissions per browser context rather than per tab.
+    pub async fn emulate(&self, page: &Page, emulation: &Emulation) -> Result<()> {
+        if let Some(device) = &emulation.device {
+            self.emulate_device(page, device).await?;
+        }
+        let result = async {
+            let device_agent = emulation.device.as_ref().and_then(|device| device.user_agent.clone());
+            let user_agent = emulation.user_agent.clone().or(device_agent);
+            let accept_language = emulation.accept_language.clone().or_else(|| emulation.locale.clone());
+            if user_agent.is_some() || accept_language.is_some() {
+                let user_agent = match user_agent {
+                    Some(user_agent) => user_agent,
+                    None => page.user_agent().await?,
+                };
+                let mut params = SetUserAgentOverrideParams::new(user_agent);
+                params.accept_language = accept_language;
+                page.execute(params).await?;
+            }
+            if let Some(locale) = &emulation.locale {
+                page.execute(SetLocaleOverrideParams { locale: Some(locale.clone()) }).await?;
+            }
+            if let Some(timezone) = &emulation.timezone {
+                page.execute(SetTimezoneOverrideParams::new(timezone.clone())).await?;
+            }
+            if let Some(Geolocation { latitude, longitude, accuracy }) = emulation.geolocation {
+                self.browser().execute(GrantPermissionsParams::new(vec![PermissionType::Geolocation])).await?;
+                let position = SetGeolocationOverrideParams {
+                    latitude: Some(latitude),
+                    longitude: Some(longitude),
+                    accuracy: Some(accuracy),
+                };
+                page.execute(position).await?;
+            }
+
+            let mut features = Vec::new();
+            if let Some(color_scheme) = emulation.color_scheme {
+                features.push(MediaFeature::new("prefers-color-scheme", color_scheme.to_string()));
+            }
+            if emulation.reduced_motion {
+                features.push(MediaFeature::new("prefers-reduced-motion", "reduce"));
+            }
+            if !features.is_empty() {
+                page.execute(SetEmulatedMediaParams { media: None, features: Some(features) }).await?;
+            }
+            if let Some(rate) = emulation.cpu_throttling {
+                page.execute(SetCpuThrottlingRateParams::new(rate)).await?;
+            }
+            Ok(())
+        }
+        .await;
+        match result {
+            Ok(()) => Ok(()),
+            Err(e) => Err(ScrapeError::cdp("emulate", page_url(page).await, e)),
+        }
+    }
+
+    /// Open `url` in a new tab emulating `emulation`, and wait for the
+    /// session's load state.
+    pub async fn open_emulated(&self, url: &str, emulation: &Emulation) -> Result<Page> {
+        let page = self.new_tab(url).await?;
+        let result = match self.emulate(&page, emulation).await {
+            Ok(()) => self.goto(&page, url).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(_) => Ok(page),
+            Err(e) => {
+                let _ = page.close().await;
+                Err(e)
+            }
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+use chromiumoxide::error::CdpError;
+use thiserror::Error;
+
+/// Everything that can go wrong while scraping.
+///
+/// Variants carry the `step` being performed (e.g. `"open"`, `"search"`)
+/// and, where known, the page URL and selector involved, so callers can
+/// decide whether to retry, skip or alert.
+#[derive(Debug, Error)]
+pub enum ScrapeError {
+    /// The browser configuration was rejected before launch.
+    #[error("invalid browser configuration: {0}")]
+    Config(String),
+
+    /// The browser process could not be started.
+    #[error("failed to launch browser: {source}")]
+    Launch {
+        #[source]
+        source: Box<CdpError>,
+    },
+
+    /// No element matched a selector.
+    #[error("{step}: no element matches `{selector}` on {url}")]
+    SelectorNotFound {
+        step: &'static str,
+        url: String,
+        selector: String,
+        #[source]
+        source: Box<CdpError>,
+    },
+
+    /// A page did not finish loading in time.
+    #[error("{step}: navigation to {url} timed out")]
+    NavigationTimeout {
+        step: &'static str,
+        url: String,
+    },
+
+    /// A page failed to load.
+    #[error("{step}: navigation to {url} failed: {source}")]
+    Navigation {
+        step: &'static str,
+        url: String,
+        #[source]
+        source: Box<CdpError>,
+    },
+
+    /// A wait condition did not hold before its timeout.
+    #[error("{step}: timed out after {timeout:?} waiting for {condition} on {url}")]
+    WaitTimeout {
+        step: &'static str,
+        url: String,
+        condition: String,
+        timeout: Duration,
+    },
+
+    /// An extraction schema is malformed.
+    #[error("invalid extraction schema: {0}")]
+    Schema(String),
+
+    /// An extracted field is missing or could not be converted to its type.
+    #[error("{step}: field `{field}` on {url}: {message}")]
+    Field {
+        step: &'static str,
+        url: String,
+        field: String,
+        message: String,
+    },
+
+    /// A page did not contain what the step was looking for.
+    #[error("{step}: {what} not found on {url}")]
+    NotFound {
+        step: &'static str,
+        url: String,
+        what: String,
+    },
+
+    /// A screenshot differed from its baseline by more than the threshold.
+    #[error("{step}: {percent:.2}% of pixels differ from {} on {url}, over the {threshold}% threshold", baseline.display())]
+    VisualMismatch {
+        step: &'static str,
+        url: String,
+        baseline: PathBuf,
+        percent: f64,
+        threshold: f64,
+        /// Where the highlighted differences were written, if anywhere.
+        diff: Option<PathBuf>,
+    },
+
+    /// An image could not be decoded or encoded.
+    #[error("{step}: {what}: {message}")]
+    Image {
+        step: &'static str,
+        what: String,
+        message: String,
+    },
+
+    /// A key name has no definition on the US keyboard layout.
+    #[error("unknown key `{0}`")]
+    UnknownKey(String),
+
+    /// A script threw while being evaluated in the page.
+    #[error("{step}: JavaScript exception on {url}: {message}")]
+    Evaluation {
+        step: &'static str,
+        url: String,
+        message: String,
+    },
+
+    /// A value returned from the page did not have the expected shape.
+    #[error("{step}: unexpected value from {url}: {source}")]
+    Deserialize {
+        step: &'static str,
+        url: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// The connection to the browser is gone.
+    #[error("{step}: browser connection lost: {source}")]
+    BrowserClosed {
+        step: &'static str,
+        #[source]
+        source: Box<CdpError>,
+    },
+
+    /// Any other DevTools protocol failure.
+    #[error("{step}: DevTools command failed on {url}: {source}")]
+    Cdp {
+        step: &'static str,
+        url: String,
+        #[source]
+        source: Box<CdpError>,
+    },
+
+    /// Reading or writing a local file failed.
+    #[error("{step}: {}: {source}", path.display())]
+    Io {
+        step: &'static str,
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+}
+
+impl ScrapeError {
+    /// Classify a protocol error raised while performing `step` on `url`.
+    pub(crate) fn cdp(step: &'static str, url: impl Into<String>, source: CdpError) -> Self {
+        let url = url.into();
+        match source {
+            CdpError::Ws(_) | CdpError::ChannelSendError(_) | CdpError::NoResponse => {
+                ScrapeError::BrowserClosed { step, source: Box::new(source) }
+            }
+            CdpError::JavascriptException(details) => {
+                let message = details
+                    .exception
+                    .as_ref()
+                    .and_then(|exception| exception.description.clone())
+                    .unwrap_or(details.text);
+                ScrapeError::Evaluation { step, url, message }
+            }
+            source => ScrapeError::Cdp { step, url, source: Box::new(source) },
+        }
+    }
+
+    /// Classify an error raised while waiting for `url` to load.
+    pub(crate) fn navigation(step: &'static str, url: impl Into<String>, source: CdpError) -> Self {
+        match source {
+            CdpError::Timeout => ScrapeError::NavigationTimeout { step, url: url.into() },
+            CdpError::ChromeMessage(_) | CdpError::FrameNotFound(_) => {
+                ScrapeError::Navigation { step, url: url.into(), source: Box::new(source) }
+            }
+            source => Self::cdp(step, url, source),
+        }
+    }
+
+    /// Classify an error raised while looking up `selector`. Only Chrome's
+    /// answer that no node matched means the selector is missing; timeouts,
+    /// a closed browser and other failures are classified as by [`Self::cdp`].
+    pub(crate) fn selector(
+        step: &'static str,
+        url: impl Into<String>,
+        selector: impl Into<String>,
+        source: CdpError,
+    ) -> Self {
+        match source {
+            CdpError::Chrome(ref error) if error.message.to_ascii_lowercase().contains("could not find node") => {
+                ScrapeError::SelectorNotFound {
+                    step,
+                    url: url.into(),
+                    selector: selector.into(),
+                    source: Box::new(source),
+                }
+            }
+            source => Self::cdp(step, url, source),
+        }
+    }
+
+    /// Wrap a failure to deserialize a page value.
+    pub(crate) fn deserialize(step: &'static str, url: impl Into<String>, source: serde_json::Error) -> Self {
+        ScrapeError::Deserialize { step, url: url.into(), source }
+    }
+
+    /// Wrap a local file error.
+    pub fn io(step: &'static str, path: impl Into<PathBuf>, source: io::Error) -> Self {
+        ScrapeError::Io { step, path: path.into(), source }
+    }
+
+    /// The step that failed, if the error happened after launch.
+    pub fn step(&self) -> Option<&'static str> {
+        match self {
+            ScrapeError::Config(_)
+            | ScrapeError::Launch { .. }
+            | ScrapeError::Schema(_)
+            | ScrapeError::UnknownKey(_) => None,
+            ScrapeError::SelectorNotFound { step, .. }
+            | ScrapeError::NavigationTimeout { step, .. }
+            | ScrapeError::Navigation { step, .. }
+            | ScrapeError::WaitTimeout { step, .. }
+            | ScrapeError::Field { step, .. }
+            | ScrapeError::NotFound { step, .. }
+            | ScrapeError::VisualMismatch { step, .. }
+            | ScrapeError::Image { step, .. }
+            | ScrapeError::Evaluation { step, .. }
+            | ScrapeError::Deserialize { step, .. }
+            | ScrapeError::BrowserClosed { step, .. }
+            | ScrapeError::Cdp { step, .. }
+            | ScrapeError::Io { step, .. } => Some(*step),
+        }
+    }
+
+    /// The page URL involved, if any.
+    pub fn url(&self) -> Option<&str> {
+        match self {
+            ScrapeError::SelectorNotFound { url, .. }
+            | ScrapeError::NavigationTimeout { url, .. }
+            | ScrapeError::Navigation { url, .. }
+            | ScrapeError::WaitTimeout { url, .. }
+            | ScrapeError::Field { url, .. }
+            | ScrapeError::NotFound { url, .. }
+            | ScrapeError::VisualMismatch { url, .. }
+            | ScrapeError::Evaluation { url, .. }
+            | ScrapeError::Deserialize { url, .. }
+            | ScrapeError::Cdp { url, .. } => Some(url),
+            _ => None,
+        }
+    }
+
+    /// Whether retrying the same operation in the same browser might succeed.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            ScrapeError::NavigationTimeout { .. }
+                | ScrapeError::Navigation { .. }
+                | ScrapeError::WaitTimeout { .. }
+                | ScrapeError::Cdp { .. }
+        )
+    }
+}
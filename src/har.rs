@@ -0,0 +1,660 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use async_std::task::JoinHandle;
+use base64::Engine;
+use chromiumoxide::cdp::browser_protocol::network::{
+    EventDataReceived, EventLoadingFailed, EventLoadingFinished, EventRequestWillBeSent, EventResponseReceived,
+    GetResponseBodyParams, Headers, RequestId, ResourceTiming, ResourceType, Response,
+};
+use chromiumoxide::cdp::browser_protocol::page::{EventDomContentEventFired, EventLoadEventFired};
+use chromiumoxide::Page;
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::network::header_pairs;
+use crate::{Result, ScrapeError, Scraper};
+
+/// An HTTP Archive: the network log of a page, in the
+/// [HAR 1.2](http://www.softwareishard.com/blog/har-12-spec/) format browser
+/// dev tools import.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Har {
+    pub log: HarLog,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarLog {
+    pub version: String,
+    pub creator: HarCreator,
+    #[serde(default)]
+    pub pages: Vec<HarPage>,
+    pub entries: Vec<HarEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarCreator {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarPage {
+    pub started_date_time: String,
+    pub id: String,
+    pub title: String,
+    pub page_timings: HarPageTimings,
+}
+
+/// When the page's events fired, in milliseconds since it started loading.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarPageTimings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_content_load: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_load: Option<f64>,
+}
+
+/// One request and its response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarEntry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pageref: Option<String>,
+    pub started_date_time: String,
+    /// Total time taken, in milliseconds.
+    pub time: f64,
+    pub request: HarRequest,
+    pub response: HarResponse,
+    pub cache: HarCache,
+    pub timings: HarTimings,
+    #[serde(rename = "serverIPAddress", default, skip_serializing_if = "Option::is_none")]
+    pub server_ip_address: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection: Option<String>,
+    /// What requested it, as Chrome names it: `document`, `xhr`, `image`...
+    #[serde(rename = "_resourceType", default, skip_serializing_if = "Option::is_none")]
+    pub resource_type: Option<String>,
+    /// Why the request failed, such as `net::ERR_NAME_NOT_RESOLVED`.
+    #[serde(rename = "_error", default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarRequest {
+    pub method: String,
+    pub url: String,
+    pub http_version: String,
+    pub cookies: Vec<HarNameValue>,
+    pub headers: Vec<HarNameValue>,
+    pub query_string: Vec<HarNameValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_data: Option<HarPostData>,
+    /// -1 when unknown, as here: Chrome does not report it.
+    pub headers_size: i64,
+    pub body_size: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarResponse {
+    /// 0 when no response arrived.
+    pub status: i64,
+    pub status_text: String,
+    pub http_version: String,
+    pub cookies: Vec<HarNameValue>,
+    pub headers: Vec<HarNameValue>,
+    pub content: HarContent,
+    #[serde(rename = "redirectURL")]
+    pub redirect_url: String,
+    pub headers_size: i64,
+    pub body_size: i64,
+    /// Bytes received over the network, headers included.
+    #[serde(rename = "_transferSize", default, skip_serializing_if = "Option::is_none")]
+    pub transfer_size: Option<i64>,
+}
+
+/// A header, cookie or query parameter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HarNameValue {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarPostData {
+    pub mime_type: String,
+    pub text: String,
+}
+
+/// The response body, present when recorded with [`HarOptions::bodies`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarContent {
+    /// Decoded size in bytes.
+    pub size: i64,
+    pub mime_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// `base64` for binary bodies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct HarCache {}
+
+/// Phases of a request, in milliseconds; -1 for those that did not happen,
+/// such as DNS and connect on a reused connection.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarTimings {
+    pub blocked: f64,
+    pub dns: f64,
+    /// Includes `ssl`.
+    pub connect: f64,
+    pub send: f64,
+    pub wait: f64,
+    pub receive: f64,
+    pub ssl: f64,
+}
+
+/// What a HAR recording includes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HarOptions {
+    /// Include response bodies, base64-encoded when binary. Bodies are read
+    /// as each response finishes loading, so large pages make large files.
+    pub bodies: bool,
+}
+
+/// Records the network traffic of a page into a HAR file.
+///
+/// Recording runs until the page closes, then writes the file. Dropping the
+/// recorder does not stop it.
+pub struct HarRecorder {
+    state: Arc<Mutex<Recording>>,
+    writer: JoinHandle<Result<Har>>,
+}
+
+impl HarRecorder {
+    /// What has been recorded so far.
+    pub fn har(&self) -> Har {
+        self.state.lock().unwrap().har()
+    }
+
+    /// Wait for the page to close and the file to be written, and return
+    /// what it holds.
+    pub async fn finished(self) -> Result<Har> {
+        self.writer.await
+    }
+}
+
+// A directory to record every tab the helpers open into
+pub(crate) struct HarSession {
+    dir: PathBuf,
+    options: HarOptions,
+    opened: AtomicUsize,
+    // Only whether each file was written, so recordings are freed as tabs close
+    writers: Mutex<Vec<JoinHandle<Result<()>>>>,
+}
+
+impl HarSession {
+    // Wait for the recordings of closed tabs to be written
+    pub(crate) async fn finish(self) -> Result<()> {
+        let mut result = Ok(());
+        for writer in self.writers.into_inner().unwrap() {
+            if let Err(e) = writer.await {
+                result = result.and(Err(e));
+            }
+        }
+        result
+    }
+}
+
+#[derive(Default)]
+struct Recording {
+    // The tab's main frame, whose document requests start a new page
+    main_frame: String,
+    pages: Vec<PageLoad>,
+    pending: HashMap<RequestId, Pending>,
+    // Finished entries with their monotonic start time
+    entries: Vec<(f64, HarEntry)>,
+}
+
+// A document loaded in the main frame: when its request started, as (wall
+// clock, monotonic) seconds, its URL and when its events fired
+struct PageLoad {
+    wall_time: f64,
+    started: f64,
+    url: String,
+    content_loaded: Option<f64>,
+    loaded: Option<f64>,
+}
+
+struct Pending {
+    pageref: Option<String>,
+    started: f64,
+    wall_time: f64,
+    request: HarRequest,
+    resource_type: Option<ResourceType>,
+    response: Option<Response>,
+    received: i64,
+}
+
+struct Finish {
+    at: f64,
+    transferred: Option<f64>,
+    body: Option<(String, bool)>,
+    error: Option<String>,
+}
+
+enum HarEvent {
+    Started(Arc<EventRequestWillBeSent>),
+    Responded(Arc<EventResponseReceived>),
+    Received(Arc<EventDataReceived>),
+    Finished(Arc<EventLoadingFinished>),
+    Failed(Arc<EventLoadingFailed>),
+    ContentLoaded(f64),
+    Loaded(f64),
+}
+
+impl Scraper {
+    /// Record the network traffic of `page` from now on, and write it to
+    /// `path` as HAR when the page closes.
+    ///
+    /// ```ignore
+    /// let page = scraper.browser().new_page("about:blank").await?;
+    /// let recorder = scraper.record_har(&page, "failed-run.har", &HarOptions::default()).await?;
+    /// scraper.goto(&page, "https://shop.example/").await?;
+    /// page.close().await?;
+    /// let har = recorder.finished().await?;
+    /// ```
+    pub async fn record_har(&self, page: &Page, path: impl Into<PathBuf>, options: &HarOptions) -> Result<HarRecorder> {
+        let path = path.into();
+        self.start_har(page, options, move |_| path).await
+    }
+
+    // Record `page` into the file `path` names once the recording is done
+    async fn start_har(
+        &self,
+        page: &Page,
+        options: &HarOptions,
+        path: impl FnOnce(&Har) -> PathBuf + Send + 'static,
+    ) -> Result<HarRecorder> {
+        let listen = |e| ScrapeError::cdp("record HAR", "", e);
+        let main_frame = match page.mainframe().await.map_err(listen)? {
+            Some(frame) => frame.inner().clone(),
+            // Chrome gives a tab's main frame the tab's ID
+            None => page.target_id().inner().clone(),
+        };
+        let started = page.event_listener::<EventRequestWillBeSent>().await.map_err(listen)?;
+        let responded = page.event_listener::<EventResponseReceived>().await.map_err(listen)?;
+        let received = page.event_listener::<EventDataReceived>().await.map_err(listen)?;
+        let finished = page.event_listener::<EventLoadingFinished>().await.map_err(listen)?;
+        let failed = page.event_listener::<EventLoadingFailed>().await.map_err(listen)?;
+        let content_loaded = page.event_listener::<EventDomContentEventFired>().await.map_err(listen)?;
+        let loaded = page.event_listener::<EventLoadEventFired>().await.map_err(listen)?;
+        let mut events = stream::select_all([
+            started.map(HarEvent::Started).boxed(),
+            responded.map(HarEvent::Responded).boxed(),
+            received.map(HarEvent::Received).boxed(),
+            finished.map(HarEvent::Finished).boxed(),
+            failed.map(HarEvent::Failed).boxed(),
+            content_loaded.map(|e| HarEvent::ContentLoaded(*e.timestamp.inner())).boxed(),
+            loaded.map(|e| HarEvent::Loaded(*e.timestamp.inner())).boxed(),
+        ]);
+
+        let state = Arc::new(Mutex::new(Recording { main_frame, ..Recording::default() }));
+        let (recording, page, bodies) = (state.clone(), page.clone(), options.bodies);
+        // The event streams end when the page closes
+        let writer = async_std::task::spawn(async move {
+            while let Some(event) = events.next().await {
+                // Read bodies before locking, while the page still has them
+                let body = match &event {
+                    HarEvent::Finished(event) if bodies => read_body(&page, &event.request_id).await,
+                    _ => None,
+                };
+                recording.lock().unwrap().record(event, body);
+            }
+            let har = recording.lock().unwrap().har();
+            write_har(&har, &path(&har))?;
+            Ok(har)
+        });
+        Ok(HarRecorder { state, writer })
+    }
+
+    /// The directory every tab the helpers open is recorded into, if any.
+    pub fn har_dir(&self) -> Option<&Path> {
+        self.har.as_ref().map(|har| har.dir.as_path())
+    }
+
+    /// Record every tab the helpers open from now on, including pooled and
+    /// crawled ones, into a HAR file in `dir`, or stop with None. Files are
+    /// numbered in the order tabs open and named after the host of the
+    /// tab's first page, such as `003-books.toscrape.com.har`;
+    /// [`Scraper::close`] waits for them all to be written.
+    pub fn set_har_dir(&mut self, dir: Option<PathBuf>, options: HarOptions) {
+        self.har = dir.map(|dir| HarSession { dir, options, opened: AtomicUsize::new(0), writers: Mutex::default() });
+    }
+
+    // Record a tab the helpers opened for the session
+    pub(crate) async fn record_har_session(&self, page: &Page) -> Result<()> {
+        let Some(har) = &self.har else {
+            return Ok(());
+        };
+        std::fs::create_dir_all(&har.dir).map_err(|e| ScrapeError::io("record HAR", &har.dir, e))?;
+        let (dir, number) = (har.dir.clone(), har.opened.fetch_add(1, Ordering::Relaxed) + 1);
+        let path = move |written: &Har| {
+            let first = written.log.pages.first().and_then(|page| url::Url::parse(&page.title).ok());
+            let host = first.as_ref().and_then(|url| url.host_str()).unwrap_or("page");
+            dir.join(format!("{:03}-{}.har", number, host))
+        };
+        let recorder = self.start_har(page, &har.options, path).await?;
+        let writer = async_std::task::spawn(async move { recorder.finished().await.map(drop) });
+        har.writers.lock().unwrap().push(writer);
+        Ok(())
+    }
+}
+
+impl Recording {
+    fn record(&mut self, event: HarEvent, body: Option<(String, bool)>) {
+        match event {
+            HarEvent::Started(event) => {
+                let (started, wall_time) = (*event.timestamp.inner(), *event.wall_time.inner());
+                // A redirect reuses the request ID: the hop that redirected is done
+                let redirected = match &event.redirect_response {
+                    Some(redirect) => self.pending.remove(&event.request_id).map(|pending| (pending, redirect)),
+                    None => None,
+                };
+                if let Some((mut pending, redirect)) = redirected {
+                    pending.response = Some(redirect.clone());
+                    self.finish(pending, Finish { at: started, transferred: None, body: None, error: None });
+                }
+                // Redirect hops of a document belong to the page they lead to
+                let document = event.redirect_response.is_none()
+                    && event.r#type == Some(ResourceType::Document)
+                    && event.frame_id.as_ref().is_some_and(|frame| *frame.inner() == self.main_frame);
+                if document {
+                    let url = event.request.url.clone();
+                    self.pages.push(PageLoad { wall_time, started, url, content_loaded: None, loaded: None });
+                }
+                let pending = Pending {
+                    pageref: (!self.pages.is_empty()).then(|| page_id(self.pages.len())),
+                    started,
+                    wall_time,
+                    request: har_request(&event),
+                    resource_type: event.r#type.clone(),
+                    response: None,
+                    received: 0,
+                };
+                self.pending.insert(event.request_id.clone(), pending);
+            }
+            HarEvent::Responded(event) => {
+                if let Some(pending) = self.pending.get_mut(&event.request_id) {
+                    pending.response = Some(event.response.clone());
+                    pending.resource_type = Some(event.r#type.clone());
+                }
+            }
+            HarEvent::Received(event) => {
+                if let Some(pending) = self.pending.get_mut(&event.request_id) {
+                    pending.received += event.data_length;
+                }
+            }
+            HarEvent::Finished(event) => {
+                if let Some(pending) = self.pending.remove(&event.request_id) {
+                    let (at, transferred) = (*event.timestamp.inner(), Some(event.encoded_data_length));
+                    self.finish(pending, Finish { at, transferred, body, error: None });
+                }
+            }
+            HarEvent::Failed(event) => {
+                if let Some(pending) = self.pending.remove(&event.request_id) {
+                    let error = Some(event.error_text.clone());
+                    self.finish(pending, Finish { at: *event.timestamp.inner(), transferred: None, body: None, error });
+                }
+            }
+            HarEvent::ContentLoaded(timestamp) => {
+                if let Some(page) = self.pages.last_mut() {
+                    page.content_loaded = Some(timestamp);
+                }
+            }
+            HarEvent::Loaded(timestamp) => {
+                if let Some(page) = self.pages.last_mut() {
+                    page.loaded = Some(timestamp);
+                }
+            }
+        }
+    }
+
+    fn finish(&mut self, pending: Pending, finish: Finish) {
+        let timings = har_timings(&pending, &finish);
+        let time = [timings.blocked, timings.dns, timings.connect, timings.send, timings.wait, timings.receive]
+            .iter()
+            .filter(|phase| **phase > 0.)
+            .sum();
+        let response = pending.response.as_ref();
+        let http_version = response.and_then(|response| response.protocol.clone()).unwrap_or_default();
+        let mut request = pending.request;
+        request.http_version = http_version.clone();
+        if let Some(request_headers) = response.and_then(|response| response.request_headers.as_ref()) {
+            // The headers actually sent, including cookies added by the network stack
+            request.headers = name_values(request_headers);
+            request.cookies = request_cookies(&request.headers);
+        }
+        let entry = HarEntry {
+            pageref: pending.pageref,
+            started_date_time: iso8601(pending.wall_time),
+            time,
+            request,
+            response: har_response(response, http_version, pending.received, &finish),
+            cache: HarCache::default(),
+            timings,
+            server_ip_address: response.and_then(|response| response.remote_ip_address.clone()),
+            connection: response.map(|response| format!("{}", response.connection_id)),
+            resource_type: pending.resource_type.map(|kind| kind.as_ref().to_ascii_lowercase()),
+            error: finish.error,
+        };
+        self.entries.push((pending.started, entry));
+    }
+
+    fn har(&self) -> Har {
+        let mut entries = self.entries.clone();
+        entries.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        let pages = self.pages.iter().enumerate().map(|(index, page)| HarPage {
+            started_date_time: iso8601(page.wall_time),
+            id: page_id(index + 1),
+            title: page.url.clone(),
+            page_timings: HarPageTimings {
+                on_content_load: page.content_loaded.map(|at| (at - page.started) * 1000.),
+                on_load: page.loaded.map(|at| (at - page.started) * 1000.),
+            },
+        });
+        Har {
+            log: HarLog {
+                version: "1.2".to_string(),
+                creator: HarCreator {
+                    name: env!("CARGO_PKG_NAME").to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                },
+                pages: pages.collect(),
+                entries: entries.into_iter().map(|(_, entry)| entry).collect(),
+            },
+        }
+    }
+}
+
+fn page_id(number: usize) -> String {
+    format!("page_{}", number)
+}
+
+fn har_request(event: &EventRequestWillBeSent) -> HarRequest {
+    let request = &event.request;
+    let url = match &request.url_fragment {
+        Some(fragment) => format!("{}{}", request.url, fragment),
+        None => request.url.clone(),
+    };
+    let query_string = match url::Url::parse(&url) {
+        Ok(parsed) => parsed
+            .query_pairs()
+            .map(|(name, value)| HarNameValue { name: name.into_owned(), value: value.into_owned() })
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    let headers = name_values(&request.headers);
+    let post_data = request.post_data_entries.as_ref().map(|entries| {
+        let mut bytes = Vec::new();
+        for entry in entries.iter().filter_map(|entry| entry.bytes.as_ref()) {
+            let encoded: &str = entry.as_ref();
+            bytes.extend(base64::engine::general_purpose::STANDARD.decode(encoded).unwrap_or_default());
+        }
+        let mime_type = header(&headers, "content-type").unwrap_or_default().to_string();
+        HarPostData { mime_type, text: String::from_utf8_lossy(&bytes).into_owned() }
+    });
+    HarRequest {
+        method: request.method.clone(),
+        url,
+        http_version: String::new(),
+        cookies: request_cookies(&headers),
+        headers,
+        query_string,
+        body_size: post_data.as_ref().map_or(0, |post_data| post_data.text.len() as i64),
+        post_data,
+        headers_size: -1,
+    }
+}
+
+fn har_response(response: Option<&Response>, http_version: String, received: i64, finish: &Finish) -> HarResponse {
+    let Some(response) = response else {
+        return HarResponse {
+            status: 0,
+            status_text: String::new(),
+            http_version,
+            cookies: Vec::new(),
+            headers: Vec::new(),
+            content: HarContent { size: 0, mime_type: "x-unknown".to_string(), text: None, encoding: None },
+            redirect_url: String::new(),
+            headers_size: -1,
+            body_size: -1,
+            transfer_size: finish.transferred.map(|bytes| bytes as i64),
+        };
+    };
+    let headers = name_values(&response.headers);
+    let cookies = headers
+        .iter()
+        .filter(|header| header.name.eq_ignore_ascii_case("set-cookie"))
+        .flat_map(|header| header.value.lines())
+        .filter_map(|cookie| cookie.split(';').next().and_then(cookie_pair))
+        .collect();
+    let content = match &finish.body {
+        Some((body, true)) => HarContent {
+            size: base64::engine::general_purpose::STANDARD.decode(body).map_or(received, |bytes| bytes.len() as i64),
+            mime_type: response.mime_type.clone(),
+            text: Some(body.clone()),
+            encoding: Some("base64".to_string()),
+        },
+        Some((body, false)) => HarContent {
+            size: body.len() as i64,
+            mime_type: response.mime_type.clone(),
+            text: Some(body.clone()),
+            encoding: None,
+        },
+        None => HarContent { size: received, mime_type: response.mime_type.clone(), text: None, encoding: None },
+    };
+    HarResponse {
+        status: response.status,
+        status_text: response.status_text.clone(),
+        http_version,
+        cookies,
+        redirect_url: header(&headers, "location").unwrap_or_default().to_string(),
+        headers,
+        content,
+        headers_size: -1,
+        body_size: -1,
+        transfer_size: finish.transferred.map(|bytes| bytes as i64),
+    }
+}
+
+// Phases from Chrome's resource timing, whose offsets are in milliseconds
+// from `request_time`
+fn har_timings(pending: &Pending, finish: &Finish) -> HarTimings {
+    let total = (finish.at - pending.started) * 1000.;
+    let Some(timing) = pending.response.as_ref().and_then(|response| response.timing.as_ref()) else {
+        // Served from cache or memory, or never answered
+        let receive = total.max(0.);
+        return HarTimings { blocked: -1., dns: -1., connect: -1., send: 0., wait: 0., receive, ssl: -1. };
+    };
+    let span = |start: f64, end: f64| if start >= 0. { end - start } else { -1. };
+    let &ResourceTiming { request_time, dns_start, dns_end, connect_start, connect_end, ssl_start, ssl_end, .. } =
+        timing;
+    let queued = (request_time - pending.started) * 1000.;
+    let first_phase = [dns_start, connect_start, timing.send_start].into_iter().find(|start| *start >= 0.);
+    HarTimings {
+        blocked: (queued + first_phase.unwrap_or(0.)).max(0.),
+        dns: span(dns_start, dns_end),
+        connect: span(connect_start, connect_end),
+        send: (timing.send_end - timing.send_start).max(0.),
+        wait: (timing.receive_headers_end - timing.send_end).max(0.),
+        receive: ((finish.at - request_time) * 1000. - timing.receive_headers_end).max(0.),
+        ssl: span(ssl_start, ssl_end),
+    }
+}
+
+fn name_values(headers: &Headers) -> Vec<HarNameValue> {
+    header_pairs(headers).into_iter().map(|(name, value)| HarNameValue { name, value }).collect()
+}
+
+fn header<'a>(headers: &'a [HarNameValue], name: &str) -> Option<&'a str> {
+    headers.iter().find(|header| header.name.eq_ignore_ascii_case(name)).map(|header| header.value.as_str())
+}
+
+fn request_cookies(headers: &[HarNameValue]) -> Vec<HarNameValue> {
+    match header(headers, "cookie") {
+        Some(cookies) => cookies.split(';').filter_map(cookie_pair).collect(),
+        None => Vec::new(),
+    }
+}
+
+fn cookie_pair(cookie: &str) -> Option<HarNameValue> {
+    let (name, value) = cookie.trim().split_once('=')?;
+    Some(HarNameValue { name: name.to_string(), value: value.to_string() })
+}
+
+// The body of a finished response, and whether it is base64-encoded
+async fn read_body(page: &Page, request_id: &RequestId) -> Option<(String, bool)> {
+    let returned = page.execute(GetResponseBodyParams::new(request_id.clone())).await.ok()?.result;
+    Some((returned.body, returned.base64_encoded))
+}
+
+fn write_har(har: &Har, path: &Path) -> Result<()> {
+    let json = serde_json::to_string_pretty(har).expect("HAR serializes to JSON");
+    std::fs::write(path, json).map_err(|e| ScrapeError::io("record HAR", path, e))
+}
+
+// `seconds` since the Unix epoch as an ISO 8601 UTC date, in milliseconds
+fn iso8601(seconds: f64) -> String {
+    let millis = (seconds * 1000.).round() as i64;
+    let (days, time) = (millis.div_euclid(86_400_000), millis.rem_euclid(86_400_000));
+    // Civil date from a day count, after Howard Hinnant's `civil_from_days`
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        time / 3_600_000,
+        time / 60_000 % 60,
+        time / 1000 % 60,
+        time % 1000
+    )
+}
@@ -0,0 +1,434 @@
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use base64::Engine;
+use chromiumoxide::cdp::browser_protocol::fetch::{
+    ContinueRequestParams, EnableParams, EventRequestPaused, FailRequestParams, FulfillRequestParams, HeaderEntry,
+};
+use chromiumoxide::cdp::browser_protocol::network::{ErrorReason, ResourceType};
+use chromiumoxide::Page;
+use futures::StreamExt;
+use percent_encoding::percent_decode_str;
+use regex::Regex;
+
+use crate::scraper::page_url;
+use crate::{Result, ScrapeError, Scraper};
+
+/// URLs a rule applies to: a glob, where `*` matches any run of characters
+/// and `?` any single one, or a regex. Globs must match the whole URL;
+/// regexes anywhere in it.
+#[derive(Debug, Clone)]
+pub struct UrlPattern(Regex);
+
+impl UrlPattern {
+    /// A glob such as `*://*.doubleclick.net/*` or `*.woff2`.
+    pub fn glob(glob: &str) -> Self {
+        let pattern: String = glob
+            .split('*')
+            .map(|part| part.split('?').map(regex::escape).collect::<Vec<_>>().join("."))
+            .collect::<Vec<_>>()
+            .join(".*");
+        Self(Regex::new(&format!("^{}$", pattern)).expect("escaped globs are valid regexes"))
+    }
+
+    pub fn regex(regex: Regex) -> Self {
+        Self(regex)
+    }
+
+    pub fn matches(&self, url: &str) -> bool {
+        self.0.is_match(url)
+    }
+}
+
+impl FromStr for UrlPattern {
+    type Err = String;
+
+    /// A glob, or a regex between slashes such as `/\.(png|jpe?g)$/`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.strip_prefix('/').and_then(|rest| rest.strip_suffix('/')) {
+            Some(regex) => Regex::new(regex).map(UrlPattern).map_err(|e| e.to_string()),
+            None => Ok(UrlPattern::glob(s)),
+        }
+    }
+}
+
+/// A change to the headers of outgoing requests.
+#[derive(Debug, Clone)]
+pub struct HeaderRule {
+    /// Requests to change; all of them when unset.
+    pub urls: Option<UrlPattern>,
+    pub name: String,
+    /// Value to set, replacing any the request had, or None to remove it.
+    pub value: Option<String>,
+}
+
+/// Where a mocked response's body comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MockBody {
+    Bytes(Vec<u8>),
+    /// Read when a request is answered, so the file may change in between.
+    File(PathBuf),
+}
+
+/// A response to answer requests with, without them reaching the network.
+///
+/// Mocked responses allow any origin to read them, so a page's scripts can
+/// fetch mocked third-party APIs, unless they set their own
+/// `Access-Control-Allow-Origin`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: MockBody,
+}
+
+impl MockResponse {
+    /// A 200 response with `body`.
+    pub fn body(body: impl Into<Vec<u8>>) -> Self {
+        Self { status: 200, headers: Vec::new(), body: MockBody::Bytes(body.into()) }
+    }
+
+    /// A 200 response with `value` as its JSON body.
+    pub fn json(value: &serde_json::Value) -> Self {
+        Self::body(value.to_string()).header("Content-Type", "application/json")
+    }
+
+    /// A 200 response with the contents of `path`, typed by its extension.
+    /// Answers 404 if the file cannot be read.
+    pub fn file(path: impl Into<PathBuf>) -> Self {
+        Self { status: 200, headers: Vec::new(), body: MockBody::File(path.into()) }
+    }
+
+    pub fn status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+}
+
+/// Requests to answer with a mocked response.
+#[derive(Debug, Clone)]
+pub enum Mock {
+    /// Answer requests for matching URLs with `response`.
+    Response { urls: UrlPattern, response: MockResponse },
+    /// Answer requests for URLs under `base_url` with the file at the rest of
+    /// the path under `dir`, like a static file server: `base_url` of
+    /// `https://shop.example/` maps `https://shop.example/a/b.css` to
+    /// `dir/a/b.css`. Paths are percent-decoded, paths ending in `/` serve
+    /// `index.html`, files without an extension are served as HTML, and
+    /// missing files or paths leaving `dir` answer 404.
+    Directory { base_url: String, dir: PathBuf },
+}
+
+/// What a page may load, and how its requests are rewritten or answered.
+///
+/// Mocks are checked first, then blocks; requests neither answers go to
+/// the network with the header rules applied.
+///
+/// ```ignore
+/// let rules = RequestRules::default()
+///     .mock_dir("https://books.toscrape.com/", "tests/fixtures/books")
+///     .mock(UrlPattern::glob("*://api.example.com/stock*"), MockResponse::json(&json!({ "stock": 3 })))
+///     .block_type(ResourceType::Image)
+///     .block_type(ResourceType::Font)
+///     .block_url("*://*.google-analytics.com/*")
+///     .set_header("X-Job", "books");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RequestRules {
+    /// Answer requests without the network; the first match wins.
+    pub mocks: Vec<Mock>,
+    /// Abort requests for these kinds of resources.
+    pub block_types: Vec<ResourceType>,
+    /// Abort requests for matching URLs.
+    pub block_urls: Vec<UrlPattern>,
+    /// Applied in order to the requests that are let through.
+    pub headers: Vec<HeaderRule>,
+}
+
+impl RequestRules {
+    /// Answer requests for URLs matching `urls` with `response`.
+    pub fn mock(mut self, urls: UrlPattern, response: MockResponse) -> Self {
+        self.mocks.push(Mock::Response { urls, response });
+        self
+    }
+
+    /// Serve requests for URLs under `base_url` from the files in `dir`, see
+    /// [`Mock::Directory`].
+    pub fn mock_dir(mut self, base_url: impl Into<String>, dir: impl Into<PathBuf>) -> Self {
+        self.mocks.push(Mock::Directory { base_url: base_url.into(), dir: dir.into() });
+        self
+    }
+
+    pub fn block_type(mut self, kind: ResourceType) -> Self {
+        self.block_types.push(kind);
+        self
+    }
+
+    /// Abort requests for URLs matching `glob`.
+    pub fn block_url(mut self, glob: &str) -> Self {
+        self.block_urls.push(UrlPattern::glob(glob));
+        self
+    }
+
+    /// Abort requests for URLs matching `regex`.
+    pub fn block_regex(mut self, regex: Regex) -> Self {
+        self.block_urls.push(UrlPattern::regex(regex));
+        self
+    }
+
+    /// Send `name: value` with every request, replacing any such header.
+    pub fn set_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push(HeaderRule { urls: None, name: name.into(), value: Some(value.into()) });
+        self
+    }
+
+    /// Send `name: value` with requests for matching URLs.
+    pub fn set_header_for(mut self, urls: UrlPattern, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push(HeaderRule { urls: Some(urls), name: name.into(), value: Some(value.into()) });
+        self
+    }
+
+    /// Strip the `name` header from every request.
+    pub fn remove_header(mut self, name: impl Into<String>) -> Self {
+        self.headers.push(HeaderRule { urls: None, name: name.into(), value: None });
+        self
+    }
+
+    // The status, headers and body to answer a request for `url` with, if
+    // a mock covers it
+    fn fulfill(&self, url: &str) -> Option<(u16, Vec<HeaderEntry>, Vec<u8>)> {
+        let response = self.mocks.iter().find_map(|mock| match mock {
+            Mock::Response { urls, response } => urls.matches(url).then(|| response.clone()),
+            Mock::Directory { base_url, dir } => {
+                let path = url.strip_prefix(base_url.as_str())?;
+                Some(serve_file(dir, path.split(['?', '#']).next().unwrap_or_default()))
+            }
+        })?;
+
+        let mut headers: Vec<HeaderEntry> =
+            response.headers.iter().map(|(name, value)| HeaderEntry::new(name.clone(), value.clone())).collect();
+        let has = |headers: &[HeaderEntry], name: &str| headers.iter().any(|h| h.name.eq_ignore_ascii_case(name));
+        let (status, body) = match response.body {
+            MockBody::Bytes(body) => (response.status, body),
+            MockBody::File(path) => match std::fs::read(&path) {
+                Ok(body) => {
+                    if !has(&headers, "Content-Type") {
+                        headers.push(HeaderEntry::new("Content-Type", content_type(&path)));
+                    }
+                    (response.status, body)
+                }
+                Err(_) => (404, b"Not Found".to_vec()),
+            },
+        };
+        if !has(&headers, "Access-Control-Allow-Origin") {
+            headers.push(HeaderEntry::new("Access-Control-Allow-Origin", "*"));
+        }
+        Some((status, headers, body))
+    }
+
+    fn blocks(&self, url: &str, kind: &ResourceType) -> bool {
+        self.block_types.contains(kind) || self.block_urls.iter().any(|pattern| pattern.matches(url))
+    }
+
+    // The request's headers after the header rules, or None if none apply
+    fn rewrite_headers(&self, url: &str, headers: &serde_json::Value) -> Option<Vec<HeaderEntry>> {
+        let rules: Vec<&HeaderRule> =
+            self.headers.iter().filter(|rule| rule.urls.as_ref().is_none_or(|urls| urls.matches(url))).collect();
+        if rules.is_empty() {
+            return None;
+        }
+        let mut entries: Vec<HeaderEntry> = headers
+            .as_object()
+            .into_iter()
+            .flatten()
+            .map(|(name, value)| HeaderEntry::new(name.clone(), value.as_str().unwrap_or_default()))
+            .collect();
+        for rule in rules {
+            entries.retain(|entry| !entry.name.eq_ignore_ascii_case(&rule.name));
+            if let Some(value) = &rule.value {
+                entries.push(HeaderEntry::new(rule.name.clone(), value.clone()));
+            }
+        }
+        Some(entries)
+    }
+}
+
+// The response for `path` under `dir`, refusing paths that climb out of it
+fn serve_file(dir: &Path, path: &str) -> MockResponse {
+    // Decoded first, so `..%2F` cannot step out of `dir`
+    let Ok(path) = percent_decode_str(path).decode_utf8() else {
+        return MockResponse::body("Not Found").status(404);
+    };
+    let path = path.trim_start_matches('/');
+    let mut file = dir.join(path);
+    if path.is_empty() || path.ends_with('/') {
+        file.push("index.html");
+    }
+    if Path::new(path).components().any(|component| !matches!(component, Component::Normal(_))) {
+        return MockResponse::body("Not Found").status(404);
+    }
+    MockResponse::file(file)
+}
+
+fn content_type(file: &Path) -> &'static str {
+    match file.extension().and_then(|extension| extension.to_str()) {
+        Some("html" | "htm") | None => "text/html; charset=utf-8",
+        Some("css") => "text/css",
+        Some("js" | "mjs") => "application/javascript",
+        Some("json") => "application/json",
+        Some("txt") => "text/plain; charset=utf-8",
+        Some("xml") => "application/xml",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("woff2") => "font/woff2",
+        _ => "application/octet-stream",
+    }
+}
+
+/// How many requests a page's [`RequestRules`] mocked, blocked and let
+/// through.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RequestCounts {
+    pub mocked: u64,
+    pub blocked: u64,
+    pub allowed: u64,
+}
+
+/// Applies [`RequestRules`] to a page's requests until the page closes.
+/// Clones share the same counts.
+#[derive(Debug, Clone)]
+pub struct Interceptor {
+    counts: Arc<Counts>,
+}
+
+#[derive(Debug, Default)]
+struct Counts {
+    mocked: AtomicU64,
+    blocked: AtomicU64,
+    allowed: AtomicU64,
+    // Set once the page's events stop, when it closes
+    done: AtomicBool,
+}
+
+impl Interceptor {
+    /// Requests handled so far.
+    pub fn counts(&self) -> RequestCounts {
+        RequestCounts {
+            mocked: self.counts.mocked.load(Ordering::Relaxed),
+            blocked: self.counts.blocked.load(Ordering::Relaxed),
+            allowed: self.counts.allowed.load(Ordering::Relaxed),
+        }
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        self.counts.done.load(Ordering::Relaxed)
+    }
+}
+
+impl Scraper {
+    /// The rules applied to every tab the helpers open, if any.
+    pub fn request_rules(&self) -> Option<&RequestRules> {
+        self.request_rules.as_ref()
+    }
+
+    /// Apply `rules` to every tab the helpers open from now on, including
+    /// pooled and crawled ones, or stop with None. Read each tab's counts
+    /// with [`Scraper::request_counts`].
+    pub fn set_request_rules(&mut self, rules: Option<RequestRules>) {
+        self.request_rules = rules;
+    }
+
+    /// Requests the session's rules blocked and allowed in `page` since it
+    /// was opened, or None if it was opened without them.
+    pub fn request_counts(&self, page: &Page) -> Option<RequestCounts> {
+        let interceptors = self.interceptors.lock().unwrap();
+        let (_, interceptor) = interceptors.iter().find(|(target, _)| target == page.target_id())?;
+        Some(interceptor.counts())
+    }
+
+    /// Pause every request `page` makes from now on and answer, block,
+    /// rewrite or let it through as `rules` say. Attach rules once per page: a second
+    /// interceptor would answer the same requests again.
+    pub async fn intercept(&self, page: &Page, rules: &RequestRules) -> Result<Interceptor> {
+        let paused = match page.event_listener::<EventRequestPaused>().await {
+            Ok(paused) => paused,
+            Err(e) => return Err(ScrapeError::cdp("intercept", page_url(page).await, e)),
+        };
+        if let Err(e) = page.execute(EnableParams::default()).await {
+            return Err(ScrapeError::cdp("intercept", page_url(page).await, e));
+        }
+
+        let interceptor = Interceptor { counts: Arc::default() };
+        let (page, rules, counts) = (page.clone(), rules.clone(), Arc::clone(&interceptor.counts));
+        async_std::task::spawn(async move {
+            let mut paused = paused;
+            while let Some(event) = paused.next().await {
+                let url = &event.request.url;
+                // Requests vanish when the page navigates away, so failures
+                // to answer them are expected and ignored
+                if let Some((status, headers, body)) = rules.fulfill(url) {
+                    counts.mocked.fetch_add(1, Ordering::Relaxed);
+                    let mut fulfill = FulfillRequestParams::new(event.request_id.clone(), status);
+                    fulfill.response_headers = Some(headers);
+                    fulfill.body = Some(base64::engine::general_purpose::STANDARD.encode(body).into());
+                    let _ = page.execute(fulfill).await;
+                } else if rules.blocks(url, &event.resource_type) {
+                    counts.blocked.fetch_add(1, Ordering::Relaxed);
+                    let fail = FailRequestParams::new(event.request_id.clone(), ErrorReason::BlockedByClient);
+                    let _ = page.execute(fail).await;
+                } else {
+                    counts.allowed.fetch_add(1, Ordering::Relaxed);
+                    let mut resume = ContinueRequestParams::new(event.request_id.clone());
+                    resume.headers = rules.rewrite_headers(url, event.request.headers.inner());
+                    let _ = page.execute(resume).await;
+                }
+            }
+            counts.done.store(true, Ordering::Relaxed);
+        });
+        Ok(interceptor)
+    }
+
+    // Attach the session's rules to a new tab and keep its counts
+    pub(crate) async fn intercept_session(&self, page: &Page) -> Result<()> {
+        let Some(rules) = &self.request_rules else {
+            return Ok(());
+        };
+        let interceptor = self.intercept(page, rules).await?;
+        let mut interceptors = self.interceptors.lock().unwrap();
+        interceptors.retain(|(_, interceptor)| !interceptor.is_done());
+        interceptors.push((page.target_id().clone(), interceptor));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(rules: &RequestRules, url: &str) -> Option<u16> {
+        rules.fulfill(url).map(|(status, _, _)| status)
+    }
+
+    #[test]
+    fn directory_mocks_stay_inside_their_directory() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/intercept");
+        let rules = RequestRules::default().mock_dir("https://shop.example/", dir);
+        assert_eq!(status(&rules, "https://shop.example/"), Some(200));
+        assert_eq!(status(&rules, "https://shop.example/index%2Ehtml?v=1"), Some(200));
+        assert_eq!(status(&rules, "https://shop.example/missing.css"), Some(404));
+        assert_eq!(status(&rules, "https://shop.example/..%2F..%2FCargo.toml"), Some(404));
+        assert_eq!(status(&rules, "https://shop.example/%2e%2e/%2e%2e/Cargo.toml"), Some(404));
+        assert_eq!(status(&rules, "https://shop.example/%2Fetc%2Fpasswd"), Some(404));
+        assert_eq!(status(&rules, "https://shop.example/%FF"), Some(404));
+        assert_eq!(status(&rules, "https://other.example/"), None);
+    }
+}
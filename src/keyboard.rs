@@ -0,0 +1,243 @@
+use std::time::Duration;
+use chromiumoxide::cdp::browser_protocol::input::{DispatchKeyEventParams, DispatchKeyEventType, InsertTextParams};
+use chromiumoxide::keys::{get_key_definition, KeyDefinition};
+use chromiumoxide::Page;
+
+use crate::scraper::{js_string, page_url};
+use crate::{Result, ScrapeError, Scraper, WaitFor};
+
+/// Modifier keys held during a key event, in CDP's bit encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Modifiers(i64);
+
+impl Modifiers {
+    pub const NONE: Modifiers = Modifiers(0);
+    pub const ALT: Modifiers = Modifiers(1);
+    pub const CONTROL: Modifiers = Modifiers(2);
+    pub const META: Modifiers = Modifiers(4);
+    pub const SHIFT: Modifiers = Modifiers(8);
+
+    /// The modifier a key name stands for, if it is one.
+    fn of_key(key: &str) -> Option<Modifiers> {
+        match key {
+            "Alt" => Some(Modifiers::ALT),
+            "Control" => Some(Modifiers::CONTROL),
+            "Meta" => Some(Modifiers::META),
+            "Shift" => Some(Modifiers::SHIFT),
+            _ => None,
+        }
+    }
+
+    pub fn contains(self, other: Modifiers) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    // Whether key presses under these modifiers are shortcuts rather than text
+    fn suppresses_text(self) -> bool {
+        self.0 & (Modifiers::ALT.0 | Modifiers::CONTROL.0 | Modifiers::META.0) != 0
+    }
+}
+
+impl std::ops::BitOr for Modifiers {
+    type Output = Modifiers;
+
+    fn bitor(self, rhs: Modifiers) -> Modifiers {
+        Modifiers(self.0 | rhs.0)
+    }
+}
+
+/// Native keyboard input for a page through `Input.dispatchKeyEvent`.
+///
+/// Key names follow the DOM `KeyboardEvent.key` values of a US layout
+/// (`"a"`, `"A"`, `"Enter"`, `"ArrowLeft"`, `"Control"`...). Modifiers
+/// pressed with [`Keyboard::down`] stay held until released with
+/// [`Keyboard::up`] and apply to every key event in between. Held Shift
+/// uppercases the letters typed, but does not turn `1` into `!`.
+pub struct Keyboard {
+    page: Page,
+    modifiers: Modifiers,
+}
+
+impl Keyboard {
+    pub fn new(page: &Page) -> Self {
+        Self { page: page.clone(), modifiers: Modifiers::NONE }
+    }
+
+    /// Modifiers currently held down.
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    /// Press `key` without releasing it.
+    pub async fn down(&mut self, key: &str) -> Result<()> {
+        let definition = definition(key)?;
+        if let Some(modifier) = Modifiers::of_key(definition.key) {
+            self.modifiers = self.modifiers | modifier;
+        }
+        let text = self.text_for(definition);
+        let kind = if text.is_some() { DispatchKeyEventType::KeyDown } else { DispatchKeyEventType::RawKeyDown };
+        let mut event = key_event(kind, definition, self.modifiers);
+        event.unmodified_text = text.clone();
+        event.text = text;
+        self.dispatch(event).await
+    }
+
+    /// Release `key`.
+    pub async fn up(&mut self, key: &str) -> Result<()> {
+        let definition = definition(key)?;
+        if let Some(modifier) = Modifiers::of_key(definition.key) {
+            self.modifiers = Modifiers(self.modifiers.0 & !modifier.0);
+        }
+        self.dispatch(key_event(DispatchKeyEventType::KeyUp, definition, self.modifiers)).await
+    }
+
+    /// Press and release `key`.
+    pub async fn press(&mut self, key: &str) -> Result<()> {
+        self.down(key).await?;
+        self.up(key).await
+    }
+
+    /// Press a combination such as `"Control+A"` or `"Shift+ArrowLeft"`:
+    /// every key goes down in order, then they are released in reverse.
+    /// `Ctrl`, `Cmd` and `Option` are accepted as aliases. If a key cannot
+    /// be pressed, the ones already down are released before the error is
+    /// returned, so no modifier stays held.
+    pub async fn chord(&mut self, chord: &str) -> Result<()> {
+        let keys: Vec<&str> = chord.split('+').map(|key| alias(key.trim())).collect();
+        for (pressed, key) in keys.iter().enumerate() {
+            if let Err(e) = self.down(key).await {
+                for key in keys[..pressed].iter().rev() {
+                    let _ = self.up(key).await;
+                }
+                return Err(e);
+            }
+        }
+        // Every key is released even if one fails; the first failure is returned
+        let mut released = Ok(());
+        for key in keys.iter().rev() {
+            let up = self.up(key).await;
+            released = released.and(up);
+        }
+        released
+    }
+
+    /// Type `text` into the focused element, one key press per character,
+    /// pausing `delay` between characters. Characters without a key on a US
+    /// layout are inserted as text.
+    pub async fn type_text(&mut self, text: &str, delay: Duration) -> Result<()> {
+        let mut buffer = [0; 4];
+        for c in text.chars() {
+            let key = match c {
+                '\n' => "Enter",
+                '\t' => "Tab",
+                c => c.encode_utf8(&mut buffer),
+            };
+            if get_key_definition(key).is_some() {
+                self.press(key).await?;
+            } else {
+                let insert = InsertTextParams::new(key);
+                if let Err(e) = self.page.execute(insert).await {
+                    return Err(ScrapeError::cdp("type", page_url(&self.page).await, e));
+                }
+            }
+            if !delay.is_zero() {
+                async_std::task::sleep(delay).await;
+            }
+        }
+        Ok(())
+    }
+
+    // The text a key press types. Shift uppercases letters; other shifted
+    // characters, such as `!` for `1`, have no definition to come from.
+    fn text_for(&self, definition: &KeyDefinition) -> Option<String> {
+        if self.modifiers.suppresses_text() {
+            return None;
+        }
+        let text = match definition.text {
+            Some(text) => text,
+            None if definition.key.chars().count() == 1 => definition.key,
+            None => return None,
+        };
+        if self.modifiers.contains(Modifiers::SHIFT) && text.chars().count() == 1 {
+            return Some(text.to_uppercase());
+        }
+        Some(text.to_string())
+    }
+
+    async fn dispatch(&self, event: DispatchKeyEventParams) -> Result<()> {
+        match self.page.execute(event).await {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ScrapeError::cdp("keyboard", page_url(&self.page).await, e)),
+        }
+    }
+}
+
+fn definition(key: &str) -> Result<&'static KeyDefinition> {
+    get_key_definition(key).ok_or_else(|| ScrapeError::UnknownKey(key.to_string()))
+}
+
+fn alias(key: &str) -> &str {
+    match key {
+        "Ctrl" => "Control",
+        "Cmd" | "Command" => "Meta",
+        "Option" => "Alt",
+        key => key,
+    }
+}
+
+fn key_event(kind: DispatchKeyEventType, definition: &KeyDefinition, modifiers: Modifiers) -> DispatchKeyEventParams {
+    let mut event = DispatchKeyEventParams::new(kind);
+    event.modifiers = Some(modifiers.0);
+    event.key = Some(definition.key.to_string());
+    event.code = Some(definition.code.to_string());
+    event.windows_virtual_key_code = Some(definition.key_code);
+    event.native_virtual_key_code = Some(definition.key_code);
+    event
+}
+
+/// How [`Scraper::type_into`] enters text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TypeOptions {
+    /// Select and delete the field's existing contents first.
+    pub clear: bool,
+    /// Pause between characters, for sites that debounce input.
+    pub delay: Duration,
+}
+
+impl Scraper {
+    /// A keyboard bound to `page`.
+    pub fn keyboard(&self, page: &Page) -> Keyboard {
+        Keyboard::new(page)
+    }
+
+    /// Focus the element matching `selector` and type `text` into it.
+    ///
+    /// Waits for the element to be visible, focuses it and checks that it
+    /// actually holds focus before the first key event, so no characters are
+    /// lost to a field that is still settling.
+    pub async fn type_into(&self, page: &Page, selector: &str, text: &str, options: TypeOptions) -> Result<()> {
+        self.wait(page, &WaitFor::visible(selector)).await?;
+        let element = self.find(page, selector).await?;
+        if let Err(e) = element.click().await {
+            return Err(ScrapeError::cdp("type", page_url(page).await, e));
+        }
+        let focus = format!(
+            "(() => {{ const element = document.querySelector({}); element.focus(); \
+             return document.activeElement === element; }})()",
+            js_string(selector)
+        );
+        self.wait(page, &WaitFor::predicate(focus)).await?;
+
+        let mut keyboard = self.keyboard(page);
+        if options.clear {
+            keyboard.chord("Control+a").await?;
+            keyboard.press("Backspace").await?;
+        }
+        keyboard.type_text(text, options.delay).await
+    }
+
+    /// Press and release `key` on whatever element has focus.
+    pub async fn press(&self, page: &Page, key: &str) -> Result<()> {
+        self.keyboard(page).press(key).await
+    }
+}
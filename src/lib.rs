@@ -0,0 +1,69 @@
+//! Headless Chrome scraping on top of [`chromiumoxide`].
+//!
+//! The [`Scraper`] type owns a launched browser together with the task that
+//! drives its CDP connection, and exposes navigation, extraction,
+//! screenshot and PDF helpers as methods. Browsers are usually launched from a
+//! [`ScraperConfig`] loaded from TOML and the environment. Failures are
+//! reported as [`ScrapeError`].
+
+// Lets `#[derive(Scrape)]` output, which names `::rust_scraper`, work here too
+extern crate self as rust_scraper;
+
+mod capture;
+mod config;
+mod console;
+mod crawler;
+mod device;
+mod emulation;
+mod error;
+mod har;
+mod intercept;
+mod keyboard;
+mod navigation;
+mod network;
+mod pagination;
+mod pdf;
+mod pool;
+mod schema;
+mod scraper;
+mod screenshot;
+pub mod sites;
+mod typed;
+mod visual;
+mod wait;
+
+pub use capture::{CapturedResponse, ResponseCapture};
+pub use config::{parse_window_size, BrowserMode, BrowserSettings, ScraperConfig, Timeouts, CONFIG_PATH_VAR};
+pub use console::{ConsoleCallback, ConsoleCollector, ConsoleLevel, ConsoleMessage, ConsoleOptions, ConsoleSource};
+pub use crawler::{normalize_url, CrawlOptions, CrawlOutput, CrawledPage, Crawler};
+pub use device::{contact_sheet_png, Device, DeviceCapture, DeviceScreenshots};
+pub use emulation::{ColorScheme, Emulation, Geolocation};
+pub use error::ScrapeError;
+pub use har::{
+    Har, HarCache, HarContent, HarCreator, HarEntry, HarLog, HarNameValue, HarOptions, HarPage, HarPageTimings,
+    HarPostData, HarRecorder, HarRequest, HarResponse, HarTimings,
+};
+pub use intercept::{HeaderRule, Interceptor, Mock, MockBody, MockResponse, RequestCounts, RequestRules, UrlPattern};
+pub use keyboard::{Keyboard, Modifiers, TypeOptions};
+pub use navigation::{LoadState, Navigation, NavigationWatch};
+pub use network::Redirect;
+pub use pagination::{NextPage, Paginated, Pagination, PaginationEnd};
+pub use pdf::{Margins, PaperSize, PdfOptions};
+pub use pool::{PagePool, PoolOptions};
+pub use schema::{Field, FieldType, Schema, Source};
+pub use scraper::{Scraper, SearchForm};
+pub use screenshot::{Clip, ImageFormat, Region, ScreenshotOptions};
+pub use typed::{FieldError, FromField, Record, Scrape};
+pub use visual::{DiffOptions, Mask, VisualDiff};
+pub use wait::{WaitFor, WaitOptions};
+
+/// Derive [`Scrape`] for a struct from `#[scrape(...)]` field attributes.
+pub use scrape_derive::Scrape;
+
+// Re-exported so callers can configure the browser and work with pages
+// without depending on chromiumoxide directly.
+pub use chromiumoxide::cdp::browser_protocol::network::ResourceType;
+pub use chromiumoxide::{BrowserConfig, Page};
+
+/// Result type used throughout the crate.
+pub type Result<T> = std::result::Result<T, ScrapeError>;
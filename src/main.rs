@@ -1,3 +1,754 @@
-fn main() {
-    println!("Hello, world!");
-}
\ No newline at end of file
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+use clap::{Args, Parser, Subcommand};
+use futures::TryStreamExt;
+use regex::Regex;
+use rust_scraper::sites::books::BooksToScrape;
+use rust_scraper::sites::wikipedia::Wikipedia;
+use rust_scraper::{
+    BrowserMode, Clip, ColorScheme, ConsoleLevel, ConsoleOptions, CrawlOptions, Device, DiffOptions, Emulation,
+    Geolocation, HarOptions, ImageFormat, LoadState, Margins, Mask, Page, Pagination, PaperSize, PdfOptions,
+    PoolOptions, Region, RequestRules, ResourceType, Result, Schema, ScrapeError, Scraper, ScraperConfig,
+    ScreenshotOptions, SearchForm, UrlPattern, WaitFor,
+};
+
+// Command line interface for the scraper
+#[derive(Debug, Parser)]
+#[command(name = "scrape", version, about = "Headless Chrome scraping operations")]
+struct Cli {
+    #[command(flatten)]
+    browser: BrowserArgs,
+
+    #[command(flatten)]
+    emulation: EmulationArgs,
+
+    #[command(flatten)]
+    requests: RequestArgs,
+
+    #[command(flatten)]
+    record: RecordArgs,
+
+    /// Load state to wait for after navigating: domcontentloaded, load, networkidle0 or networkidle2
+    #[arg(long, global = true, default_value_t = LoadState::Load)]
+    wait_until: LoadState,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+// Options shared by every subcommand that control how Chrome is launched.
+// These take precedence over the config file and SCRAPER_* environment variables.
+#[derive(Debug, Args)]
+struct BrowserArgs {
+    /// TOML config file (defaults to $SCRAPER_CONFIG when set)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Show the browser UI instead of running headless
+    #[arg(long, global = true)]
+    headful: bool,
+
+    /// Browser window size as WIDTHxHEIGHT
+    #[arg(long, global = true, value_parser = parse_window_size)]
+    window_size: Option<(u32, u32)>,
+
+    /// Path to the Chrome/Chromium executable (auto-detected when omitted)
+    #[arg(long, global = true)]
+    chrome: Option<PathBuf>,
+
+    /// Extra command line argument passed to Chrome (repeatable)
+    #[arg(long = "chrome-arg", global = true, allow_hyphen_values = true)]
+    chrome_args: Vec<String>,
+
+    /// Proxy server for all browser traffic
+    #[arg(long, global = true)]
+    proxy: Option<String>,
+
+    /// Disable Chrome's sandbox (often required in containers)
+    #[arg(long, global = true)]
+    no_sandbox: bool,
+}
+
+// Options shared by every subcommand that change what pages see of the
+// browser, applied to each tab before it navigates
+#[derive(Debug, Args)]
+struct EmulationArgs {
+    /// Emulate a device: iphone-15, pixel-8, ipad-air or desktop-1080p
+    #[arg(long, global = true, value_parser = parse_device)]
+    emulate_device: Option<Device>,
+
+    /// User agent to send instead of the browser's own
+    #[arg(long, global = true)]
+    user_agent: Option<String>,
+
+    /// Accept-Language header, e.g. "fr-FR,fr;q=0.9" (defaults to --locale)
+    #[arg(long, global = true)]
+    accept_language: Option<String>,
+
+    /// Locale for dates and numbers, e.g. fr-FR
+    #[arg(long, global = true)]
+    locale: Option<String>,
+
+    /// Time zone, e.g. Europe/Paris
+    #[arg(long, global = true)]
+    timezone: Option<String>,
+
+    /// Position to report, as LATITUDE,LONGITUDE[,ACCURACY]
+    #[arg(long, global = true)]
+    geolocation: Option<Geolocation>,
+
+    /// Preferred color scheme: light or dark
+    #[arg(long, global = true)]
+    color_scheme: Option<ColorScheme>,
+
+    /// Ask pages to reduce motion
+    #[arg(long, global = true)]
+    reduced_motion: bool,
+
+    /// Slow the CPU down by this factor, e.g. 4
+    #[arg(long, global = true)]
+    cpu_throttling: Option<f64>,
+}
+
+impl EmulationArgs {
+    // The emulation to apply to every tab, or None to leave pages alone
+    fn emulation(self) -> Option<Emulation> {
+        let emulation = Emulation {
+            device: self.emulate_device,
+            user_agent: self.user_agent,
+            accept_language: self.accept_language,
+            locale: self.locale,
+            timezone: self.timezone,
+            geolocation: self.geolocation,
+            color_scheme: self.color_scheme,
+            reduced_motion: self.reduced_motion,
+            cpu_throttling: self.cpu_throttling,
+        };
+        (emulation != Emulation::default()).then_some(emulation)
+    }
+}
+
+// Options shared by every subcommand that block or rewrite the requests
+// pages make
+#[derive(Debug, Args)]
+struct RequestArgs {
+    /// Block a kind of resource: image, font, media, stylesheet, script, ... (repeatable)
+    #[arg(long = "block", global = true)]
+    block_types: Vec<ResourceType>,
+
+    /// Block URLs matching a glob, or a regex between slashes (repeatable)
+    #[arg(long = "block-url", global = true)]
+    block_urls: Vec<UrlPattern>,
+
+    /// Send a header with every request, as "NAME: VALUE" (repeatable)
+    #[arg(long = "header", global = true, value_parser = parse_header)]
+    headers: Vec<(String, String)>,
+
+    /// Serve URLs under a base URL from local files, as BASE_URL=DIR (repeatable)
+    #[arg(long = "mock-dir", global = true, value_parser = parse_mock_dir)]
+    mock_dirs: Vec<(String, PathBuf)>,
+}
+
+impl RequestArgs {
+    // The rules to apply to every tab, or None to let requests through untouched
+    fn rules(self) -> Option<RequestRules> {
+        let rules = RequestRules { block_types: self.block_types, block_urls: self.block_urls, ..Default::default() };
+        let rules = self.headers.into_iter().fold(rules, |rules, (name, value)| rules.set_header(name, value));
+        let rules = self.mock_dirs.into_iter().fold(rules, |rules, (base_url, dir)| rules.mock_dir(base_url, dir));
+        let empty = rules.mocks.is_empty() && rules.block_types.is_empty() && rules.block_urls.is_empty();
+        (!empty || !rules.headers.is_empty()).then_some(rules)
+    }
+}
+
+// Options shared by every subcommand that record what pages load and log
+#[derive(Debug, Args)]
+struct RecordArgs {
+    /// Write the network traffic of every tab to a HAR file in this directory
+    #[arg(long, global = true)]
+    har_dir: Option<PathBuf>,
+
+    /// Include response bodies in HAR files
+    #[arg(long, global = true, requires = "har_dir")]
+    har_bodies: bool,
+
+    /// Print what pages log to the console, from this level up: debug, info, warning or error
+    #[arg(long, global = true)]
+    console: Option<ConsoleLevel>,
+}
+
+impl RecordArgs {
+    // Console messages go to stderr, keeping stdout for results
+    fn console(&self) -> Option<ConsoleOptions> {
+        let level = self.console?;
+        Some(ConsoleOptions::default().level(level).on_message(|message| eprintln!("{}", message)))
+    }
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Search Wikipedia and print where the search leads as JSON: an article, results or a disambiguation page
+    Search {
+        /// Term to search for
+        term: String,
+
+        /// Site to open before searching
+        #[arg(long, default_value = "https://en.wikipedia.org")]
+        url: String,
+
+        /// Selector of the element that reveals the search box
+        #[arg(long, default_value = "#p-search > a")]
+        toggle_selector: String,
+
+        /// Selector of the search input field
+        #[arg(long, default_value = "input[name='search']")]
+        input_selector: String,
+
+        /// Print the article the search leads to, or its best result or meaning, with sections, infobox and links
+        #[arg(long)]
+        article: bool,
+
+        /// Pages of search results to read when the search lists results
+        #[arg(long, default_value_t = 1, conflicts_with = "article")]
+        result_pages: usize,
+    },
+    /// Capture a screenshot of a page
+    Screenshot {
+        /// Page to capture
+        url: String,
+
+        /// File to write the screenshot to
+        #[arg(short, long, default_value = "screenshot.png")]
+        output: PathBuf,
+
+        /// Capture once an element matching this selector is visible
+        #[arg(long)]
+        wait_for: Option<String>,
+
+        /// Otherwise, capture once the network has been idle this long, in milliseconds
+        #[arg(long, default_value_t = 500, conflicts_with = "wait_for")]
+        idle_ms: u64,
+
+        /// Capture the whole page rather than the viewport
+        #[arg(long)]
+        full_page: bool,
+
+        /// Capture only the first element matching this selector
+        #[arg(long, conflicts_with = "full_page")]
+        element: Option<String>,
+
+        /// Capture only this rectangle of the page, as X,Y,WIDTH,HEIGHT in CSS pixels
+        #[arg(long, value_parser = parse_clip, conflicts_with_all = ["full_page", "element"])]
+        clip: Option<Clip>,
+
+        /// Image format: png, jpeg or webp (defaults to the output file's extension)
+        #[arg(long)]
+        format: Option<ImageFormat>,
+
+        /// JPEG or WebP quality, from 0 to 100
+        #[arg(long, value_parser = clap::value_parser!(u8).range(0..=100))]
+        quality: Option<u8>,
+
+        /// Image pixels per CSS pixel, e.g. 2 for a high-density capture
+        #[arg(long)]
+        scale: Option<f64>,
+
+        /// Keep the page's default background transparent (PNG and WebP)
+        #[arg(long)]
+        transparent: bool,
+
+        /// Compare the capture with this image instead of saving it, creating it when missing
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+
+        /// Write an image highlighting the differences from the baseline to this file
+        #[arg(long, requires = "baseline")]
+        diff: Option<PathBuf>,
+
+        /// Fail when more than this percentage of pixels differ from the baseline
+        #[arg(long, default_value_t = 0.1, requires = "baseline")]
+        threshold: f64,
+
+        /// Ignore elements matching this selector when comparing (repeatable)
+        #[arg(long, requires = "baseline")]
+        mask: Vec<String>,
+
+        /// Ignore this rectangle, as X,Y,WIDTH,HEIGHT, when comparing (repeatable)
+        #[arg(long, value_parser = parse_clip, requires = "baseline")]
+        mask_rect: Vec<Clip>,
+    },
+    /// Capture a page as it looks on several devices, plus a contact sheet of them all
+    Devices {
+        /// Page to capture
+        url: String,
+
+        /// Device to emulate: iphone-15, pixel-8, ipad-air or desktop-1080p (repeatable; all by default)
+        #[arg(long = "device", value_parser = parse_device)]
+        devices: Vec<Device>,
+
+        /// Directory to write the screenshots to
+        #[arg(short, long, default_value = "screenshots")]
+        output: PathBuf,
+
+        /// Capture once an element matching this selector is visible
+        #[arg(long)]
+        wait_for: Option<String>,
+
+        /// Otherwise, capture once the network has been idle this long, in milliseconds
+        #[arg(long, default_value_t = 500, conflicts_with = "wait_for")]
+        idle_ms: u64,
+
+        /// Capture the whole page rather than the viewport
+        #[arg(long)]
+        full_page: bool,
+    },
+    /// Print a page to PDF (headless only)
+    Pdf {
+        /// Page to print
+        url: String,
+
+        /// File to write the PDF to
+        #[arg(short, long, default_value = "page.pdf")]
+        output: PathBuf,
+
+        /// Paper size: letter, legal, tabloid, a3, a4, a5 or WIDTHxHEIGHT in inches
+        #[arg(long, default_value_t = PaperSize::Letter)]
+        paper: PaperSize,
+
+        /// Margin on every side, in inches
+        #[arg(long, default_value_t = 0.4)]
+        margin: f64,
+
+        /// Lay pages out in landscape orientation
+        #[arg(long)]
+        landscape: bool,
+
+        /// Print background colors and images
+        #[arg(long)]
+        background: bool,
+
+        /// HTML template for the page header, e.g. '<span class="title"></span>'
+        #[arg(long)]
+        header: Option<String>,
+
+        /// HTML template for the page footer, e.g. '<span class="pageNumber"></span>'
+        #[arg(long)]
+        footer: Option<String>,
+
+        /// Pages to print, e.g. "1-5, 8"
+        #[arg(long)]
+        pages: Option<String>,
+    },
+    /// Print the responses a page receives for matching URLs, one JSON object per line
+    Capture {
+        /// Page to load
+        url: String,
+
+        /// URLs to capture, as a glob or /regex/ (repeatable), e.g. '*/api/*'
+        #[arg(long = "pattern", required = true)]
+        patterns: Vec<UrlPattern>,
+
+        /// Stop once no matching response has arrived for this long, in milliseconds
+        #[arg(long, default_value_t = 1000)]
+        quiet_ms: u64,
+    },
+    /// Extract an attribute from every element matching a selector
+    Extract {
+        /// Pages to extract from
+        #[arg(default_value = "https://books.toscrape.com/catalogue/category/books/science_22/index.html")]
+        urls: Vec<String>,
+
+        /// Number of pages to scrape in parallel
+        #[arg(short = 'j', long, default_value_t = 4)]
+        concurrency: usize,
+
+        /// Selector of the elements to extract
+        #[arg(short, long, default_value = ".product_pod h3 a")]
+        selector: String,
+
+        /// Attribute to read from each element
+        #[arg(short, long, default_value = "title")]
+        attr: String,
+
+        /// Read the text content of each element instead of an attribute
+        #[arg(long, conflicts_with = "attr")]
+        text: bool,
+
+        /// Extract records described by this JSON or YAML schema, printed as JSON lines
+        #[arg(long, conflicts_with_all = ["selector", "attr", "text"])]
+        schema: Option<PathBuf>,
+
+        /// Also read the following pages, by following the link matching this selector (e.g. "li.next a")
+        #[arg(long)]
+        next: Option<String>,
+
+        /// Also read the following pages, by filling in {page} in this URL template
+        #[arg(long, conflicts_with = "next")]
+        page_template: Option<String>,
+
+        /// Read at most this many pages per URL when following pages
+        #[arg(long, default_value_t = 50)]
+        max_pages: usize,
+
+        /// Write the extracted values to this file, one per line, instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Scrape every book of books.toscrape.com, printing one JSON line per book
+    Books {
+        /// Only scrape these categories, by name (repeatable)
+        #[arg(long)]
+        category: Vec<String>,
+
+        /// Number of pages to scrape in parallel
+        #[arg(short = 'j', long, default_value_t = 4)]
+        concurrency: usize,
+
+        /// Site to scrape, e.g. a local copy
+        #[arg(long, default_value = rust_scraper::sites::books::BASE_URL)]
+        url: String,
+    },
+    /// Follow links from seed pages, printing one JSON line of extracted values per page
+    Crawl {
+        /// Pages to start from
+        #[arg(required = true)]
+        seeds: Vec<String>,
+
+        /// Follow links at most this many hops from a seed
+        #[arg(short, long, default_value_t = 2)]
+        depth: usize,
+
+        /// Only follow URLs matching this regex (repeatable)
+        #[arg(long, value_parser = Regex::new)]
+        include: Vec<Regex>,
+
+        /// Never follow URLs matching this regex (repeatable)
+        #[arg(long, value_parser = Regex::new)]
+        exclude: Vec<Regex>,
+
+        /// Also follow links to other sites
+        #[arg(long)]
+        any_domain: bool,
+
+        /// Stop after visiting this many pages
+        #[arg(long)]
+        max_pages: Option<usize>,
+
+        /// Number of pages to crawl in parallel
+        #[arg(short = 'j', long, default_value_t = 4)]
+        concurrency: usize,
+
+        /// Selector of the elements to extract on each page
+        #[arg(short, long, default_value = "title")]
+        selector: String,
+
+        /// Attribute to read from each element, instead of its text content
+        #[arg(short, long)]
+        attr: Option<String>,
+    },
+}
+
+// Parse a clip rectangle such as "0,0,800,600"
+fn parse_clip(value: &str) -> std::result::Result<Clip, String> {
+    let numbers: Option<Vec<f64>> = value.split(',').map(|part| part.trim().parse().ok()).collect();
+    match numbers.as_deref() {
+        Some(&[x, y, width, height]) => Ok(Clip { x, y, width, height }),
+        _ => Err(format!("expected X,Y,WIDTH,HEIGHT, got `{value}`")),
+    }
+}
+
+// Look up a device preset such as "iphone-15"
+fn parse_device(value: &str) -> std::result::Result<Device, String> {
+    Device::preset(value).ok_or_else(|| {
+        let names: Vec<String> = Device::presets().iter().map(Device::slug).collect();
+        format!("unknown device `{value}` (expected one of {})", names.join(", "))
+    })
+}
+
+// Parse a request header such as "X-Job: books"
+fn parse_header(value: &str) -> std::result::Result<(String, String), String> {
+    match value.split_once(':') {
+        Some((name, header)) if !name.trim().is_empty() => Ok((name.trim().to_string(), header.trim().to_string())),
+        _ => Err(format!("expected NAME: VALUE, got `{value}`")),
+    }
+}
+
+// Parse a mocked directory such as "https://books.toscrape.com/=tests/fixtures/books"
+fn parse_mock_dir(value: &str) -> std::result::Result<(String, PathBuf), String> {
+    match value.rsplit_once('=') {
+        Some((base_url, dir)) if !base_url.is_empty() && !dir.is_empty() => {
+            Ok((base_url.to_string(), PathBuf::from(dir)))
+        }
+        _ => Err(format!("expected BASE_URL=DIR, got `{value}`")),
+    }
+}
+
+// Parse a window size such as "1280x800"
+fn parse_window_size(value: &str) -> std::result::Result<(u32, u32), String> {
+    rust_scraper::parse_window_size(value).ok_or_else(|| format!("expected WIDTHxHEIGHT, got `{value}`"))
+}
+
+#[async_std::main]
+async fn main() -> ExitCode {
+    match scrape(Cli::parse()).await {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn scrape(cli: Cli) -> Result<ExitCode> {
+    // Launch a browser with the configuration given on the command line
+    let mut scraper = Scraper::from_config(&load_config(&cli.browser)?).await?;
+    scraper.set_load_state(cli.wait_until);
+    scraper.set_emulation(cli.emulation.emulation());
+    scraper.set_request_rules(cli.requests.rules());
+    scraper.set_console(cli.record.console());
+    scraper.set_har_dir(cli.record.har_dir, HarOptions { bodies: cli.record.har_bodies });
+
+    let result = run(&scraper, cli.command).await;
+
+    // Clean up, even if the command failed, whose error comes first
+    let closed = scraper.close().await;
+    let code = result?;
+    closed?;
+    Ok(code)
+}
+
+// Dispatch a subcommand against a running browser. Commands that report
+// failures per item, such as `books`, exit with a failure status if any did.
+async fn run(scraper: &Scraper, command: Command) -> Result<ExitCode> {
+    match command {
+        Command::Search { term, url, article: true, .. } => {
+            let article = Wikipedia::with_base_url(scraper, &url)?.search_article(&term).await?;
+            println!("{}", serde_json::to_string_pretty(&article).expect("articles serialize to JSON"));
+        }
+        Command::Search { term, url, toggle_selector, input_selector, result_pages, .. } => {
+            let form = SearchForm { url, toggle_selector, input_selector, result_pages };
+            let outcome = scraper.search(&form, &term).await?;
+            println!("{}", serde_json::to_string_pretty(&outcome).expect("outcomes serialize to JSON"));
+        }
+        Command::Screenshot {
+            url,
+            output,
+            wait_for,
+            idle_ms,
+            full_page,
+            element,
+            clip,
+            format,
+            quality,
+            scale,
+            transparent,
+            baseline,
+            diff,
+            threshold,
+            mask,
+            mask_rect,
+        } => {
+            let ready = match wait_for {
+                Some(selector) => WaitFor::Visible(selector),
+                None => WaitFor::NetworkIdle(Duration::from_millis(idle_ms)),
+            };
+            let region = match (full_page, element, clip) {
+                (true, _, _) => Region::FullPage,
+                (_, Some(selector), _) => Region::Element(selector),
+                (_, _, Some(clip)) => Region::Clip(clip),
+                _ => Region::Viewport,
+            };
+            let options = ScreenshotOptions {
+                region,
+                format: format.or_else(|| ImageFormat::from_path(&output)).unwrap_or_default(),
+                quality,
+                device_scale_factor: scale,
+                transparent,
+            };
+            match baseline {
+                Some(baseline) => {
+                    let masks = mask.into_iter().map(Mask::Selector).chain(mask_rect.into_iter().map(Mask::Rect));
+                    let options = DiffOptions {
+                        screenshot: options,
+                        masks: masks.collect(),
+                        threshold,
+                        diff_path: diff,
+                        ..DiffOptions::default()
+                    };
+                    let result = scraper.take_screenshot_compared(&url, &baseline, &ready, &options).await?;
+                    if result.new_baseline {
+                        println!("Baseline saved to {}", baseline.display());
+                    } else {
+                        println!("{:.2}% of pixels differ from {}", result.percent, baseline.display());
+                    }
+                }
+                None => {
+                    scraper.take_screenshot_with(&url, &output, &ready, &options).await?;
+                    println!("Screenshot saved to {}", output.display());
+                }
+            }
+        }
+        Command::Devices { url, mut devices, output, wait_for, idle_ms, full_page } => {
+            let ready = match wait_for {
+                Some(selector) => WaitFor::Visible(selector),
+                None => WaitFor::NetworkIdle(Duration::from_millis(idle_ms)),
+            };
+            if devices.is_empty() {
+                devices = Device::presets();
+            }
+            let options = if full_page { ScreenshotOptions::full_page() } else { ScreenshotOptions::default() };
+            let saved = scraper.take_device_screenshots(&url, &devices, &output, &ready, &options).await?;
+            for file in &saved.files {
+                println!("Screenshot saved to {}", file.display());
+            }
+            println!("Contact sheet saved to {}", saved.contact_sheet.display());
+        }
+        Command::Capture { url, patterns, quiet_ms } => {
+            let quiet = Duration::from_millis(quiet_ms);
+            let responses = scraper.open_capturing(&url, &patterns, quiet).await?;
+            for response in responses {
+                let body = match response.json {
+                    Some(json) => json,
+                    None => serde_json::Value::String(response.text()),
+                };
+                println!("{}", serde_json::json!({ "url": response.url, "status": response.status, "body": body }));
+            }
+        }
+        Command::Pdf { url, output, paper, margin, landscape, background, header, footer, pages } => {
+            let options = PdfOptions {
+                paper,
+                margins: Margins::uniform(margin),
+                landscape,
+                print_background: background,
+                header_template: header,
+                footer_template: footer,
+                page_ranges: pages,
+            };
+            scraper.print_pdf(&url, &output, &options).await?;
+            println!("PDF saved to {}", output.display());
+        }
+        Command::Extract { urls, concurrency, selector, attr, text, schema, next, page_template, max_pages, output } => {
+            let attr = if text { None } else { Some(attr.as_str()) };
+            let schema = schema.as_deref().map(Schema::from_file).transpose()?;
+            let pagination = match (next, page_template) {
+                (Some(selector), _) => Some(Pagination::link(selector).max_pages(max_pages)),
+                (None, Some(template)) => Some(Pagination::template(template).max_pages(max_pages)),
+                (None, None) => None,
+            };
+            let pool = scraper.pool(PoolOptions { size: concurrency, ..PoolOptions::default() });
+            let tasks = urls.iter().enumerate().map(|(index, url)| {
+                let (selector, schema, pagination) = (selector.as_str(), schema.as_ref(), pagination.as_ref());
+                // Records are printed one compact JSON object per line
+                let read = move |page: Page| async move {
+                    match schema {
+                        Some(schema) => {
+                            let records = scraper.extract_records_from(&page, schema).await?;
+                            Ok(records.iter().map(|record| record.to_string()).collect())
+                        }
+                        None => scraper.extract_from(&page, selector, attr).await,
+                    }
+                };
+                move |page: Page| async move {
+                    let values = match pagination {
+                        Some(pagination) => scraper.paginate_with(&page, url, pagination, read).await?.items,
+                        None => {
+                            scraper.navigate(&page, url, scraper.load_state()).await?;
+                            read(page).await?
+                        }
+                    };
+                    Ok((index, values))
+                }
+            });
+            let mut results: Vec<(usize, Vec<String>)> = pool.run(tasks).try_collect().await?;
+            pool.close().await;
+
+            // Keep the output in the order the URLs were given
+            results.sort_by_key(|(index, _)| *index);
+            let values: Vec<String> = results.into_iter().flat_map(|(_, values)| values).collect();
+            match output {
+                Some(path) => {
+                    let mut contents = values.join("\n");
+                    contents.push('\n');
+                    std::fs::write(&path, contents).map_err(|e| ScrapeError::io("write output", &path, e))?;
+                    println!("Wrote {} values to {}", values.len(), path.display());
+                }
+                None => {
+                    for value in values {
+                        println!("{}", value);
+                    }
+                }
+            }
+        }
+        Command::Books { category, concurrency, url } => {
+            let site = BooksToScrape::with_base_url(scraper, url).concurrency(concurrency);
+            let mut categories = site.categories().await?;
+            if !category.is_empty() {
+                categories.retain(|found| category.iter().any(|name| name.eq_ignore_ascii_case(&found.name)));
+            }
+            let mut failed = 0;
+            for book in site.books_in(&categories).await? {
+                match book {
+                    Ok(book) => println!("{}", serde_json::to_string(&book).expect("books serialize to JSON")),
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        failed += 1;
+                    }
+                }
+            }
+            if failed > 0 {
+                eprintln!("error: {} books could not be scraped", failed);
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::Crawl { seeds, depth, include, exclude, any_domain, max_pages, concurrency, selector, attr } => {
+            let options = CrawlOptions {
+                max_depth: depth,
+                same_domain: !any_domain,
+                include,
+                exclude,
+                max_pages,
+                concurrency,
+                ..CrawlOptions::default()
+            };
+            let seeds: Vec<&str> = seeds.iter().map(String::as_str).collect();
+            let (selector, attr) = (selector.as_str(), attr.as_deref());
+            let outputs = scraper
+                .crawler(options)
+                .run(&seeds, |page, _| async move { scraper.extract_from(&page, selector, attr).await })
+                .await?;
+            for output in outputs {
+                match output.result {
+                    Ok(values) => {
+                        let line = serde_json::json!({ "url": output.url, "depth": output.depth, "values": values });
+                        println!("{}", line);
+                    }
+                    Err(e) => eprintln!("{}: {}", output.url, e),
+                }
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+// Load the config file and environment, then apply command line overrides
+fn load_config(args: &BrowserArgs) -> Result<ScraperConfig> {
+    let mut config = ScraperConfig::load(args.config.as_deref())?;
+    let browser = &mut config.browser;
+    if args.headful {
+        browser.mode = BrowserMode::Headful;
+    }
+    if let Some(window_size) = args.window_size {
+        browser.window_size = window_size;
+    }
+    if let Some(chrome) = &args.chrome {
+        browser.executable = Some(chrome.clone());
+    }
+    browser.args.extend(args.chrome_args.iter().cloned());
+    if let Some(proxy) = &args.proxy {
+        browser.proxy = Some(proxy.clone());
+    }
+    if args.no_sandbox {
+        browser.sandbox = false;
+    }
+    Ok(config)
+}
@@ -0,0 +1,200 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use chromiumoxide::cdp::browser_protocol::page::{EventDomContentEventFired, EventLoadEventFired, NavigateParams};
+use chromiumoxide::error::CdpError;
+use chromiumoxide::listeners::EventStream;
+use chromiumoxide::Page;
+use futures::future::{self, Either};
+use futures::{FutureExt, StreamExt};
+
+use crate::network::{NetworkTracker, Redirect};
+use crate::scraper::page_url;
+use crate::{Result, ScrapeError, Scraper};
+
+/// How long the network must stay quiet for the `networkidle` states.
+const NETWORK_IDLE: Duration = Duration::from_millis(500);
+
+/// The point in page loading a navigation waits for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoadState {
+    /// The HTML has been parsed (`DOMContentLoaded`).
+    DomContentLoaded,
+    /// The page and its subresources have loaded (`load`).
+    #[default]
+    Load,
+    /// `load` fired and no requests have been in flight for 500ms.
+    NetworkIdle0,
+    /// `load` fired and at most two requests have been in flight for 500ms.
+    NetworkIdle2,
+}
+
+impl LoadState {
+    // Requests allowed in flight while the network counts as idle
+    fn max_inflight(self) -> usize {
+        match self {
+            LoadState::NetworkIdle2 => 2,
+            _ => 0,
+        }
+    }
+}
+
+impl FromStr for LoadState {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "domcontentloaded" => Ok(LoadState::DomContentLoaded),
+            "load" => Ok(LoadState::Load),
+            "networkidle0" => Ok(LoadState::NetworkIdle0),
+            "networkidle2" => Ok(LoadState::NetworkIdle2),
+            _ => Err(format!(
+                "unknown load state `{}` (expected domcontentloaded, load, networkidle0 or networkidle2)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for LoadState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LoadState::DomContentLoaded => "domcontentloaded",
+            LoadState::Load => "load",
+            LoadState::NetworkIdle0 => "networkidle0",
+            LoadState::NetworkIdle2 => "networkidle2",
+        })
+    }
+}
+
+/// The outcome of a navigation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Navigation {
+    /// URL of the document after redirects.
+    pub url: String,
+    /// HTTP status of the main document, if a response was seen
+    /// (`about:`, `data:` and cached documents may have none).
+    pub status: Option<i64>,
+    /// Redirects followed before reaching `url`, in order.
+    pub redirects: Vec<Redirect>,
+}
+
+/// Watches a page for a navigation started by an action, such as a click
+/// or form submit. Create it before the action, then [`wait`](Self::wait).
+pub struct NavigationWatch {
+    page: Page,
+    until: LoadState,
+    timeout: Duration,
+    tracker: NetworkTracker,
+    dom_content_loaded: EventStream<EventDomContentEventFired>,
+    load: EventStream<EventLoadEventFired>,
+}
+
+impl NavigationWatch {
+    /// Wait for the page to reach the target load state.
+    pub async fn wait(mut self) -> Result<Navigation> {
+        let timeout = self.timeout;
+        match async_std::future::timeout(timeout, self.reached()).await {
+            Ok(Ok(())) => self.outcome().await,
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err(ScrapeError::NavigationTimeout { step: "navigate", url: page_url(&self.page).await }),
+        }
+    }
+
+    // Resolves once the page reaches `self.until`
+    async fn reached(&mut self) -> Result<()> {
+        let fired = match self.until {
+            LoadState::DomContentLoaded => self.dom_content_loaded.next().await.is_some(),
+            _ => self.load.next().await.is_some(),
+        };
+        if !fired {
+            return Err(ScrapeError::Navigation {
+                step: "navigate",
+                url: page_url(&self.page).await,
+                source: Box::new(CdpError::msg("page closed during navigation")),
+            });
+        }
+        if matches!(self.until, LoadState::NetworkIdle0 | LoadState::NetworkIdle2) {
+            while self.tracker.quiet_for().is_none_or(|quiet| quiet < NETWORK_IDLE) {
+                async_std::task::sleep(Duration::from_millis(50)).await;
+            }
+        }
+        Ok(())
+    }
+
+    async fn outcome(&self) -> Result<Navigation> {
+        let document = self.tracker.document();
+        if let Some(error) = document.error {
+            return Err(ScrapeError::Navigation {
+                step: "navigate",
+                url: page_url(&self.page).await,
+                source: Box::new(CdpError::ChromeMessage(error)),
+            });
+        }
+        let redirects = document.redirects;
+        Ok(match document.response {
+            Some((url, status)) => Navigation { url, status: Some(status), redirects },
+            None => Navigation { url: page_url(&self.page).await, status: None, redirects },
+        })
+    }
+}
+
+impl Scraper {
+    /// Start watching `page` for a navigation that reaches `until`.
+    pub async fn watch_navigation(&self, page: &Page, until: LoadState) -> Result<NavigationWatch> {
+        let listen = |e| ScrapeError::cdp("navigate", "", e);
+        Ok(NavigationWatch {
+            page: page.clone(),
+            until,
+            timeout: self.timeouts().navigation(),
+            tracker: NetworkTracker::attach(page, until.max_inflight()).await?,
+            dom_content_loaded: page.event_listener::<EventDomContentEventFired>().await.map_err(listen)?,
+            load: page.event_listener::<EventLoadEventFired>().await.map_err(listen)?,
+        })
+    }
+
+    /// Navigate `page` to `url` and wait until it reaches `until`.
+    ///
+    /// Fails with [`ScrapeError::Navigation`] when the document cannot be
+    /// fetched at all (DNS failure, refused connection, ...). HTTP error
+    /// statuses are not failures; check [`Navigation::status`].
+    pub async fn navigate(&self, page: &Page, url: &str, until: LoadState) -> Result<Navigation> {
+        let mut watch = self.watch_navigation(page, until).await?;
+
+        // chromiumoxide only answers `Page.navigate` once the page has loaded,
+        // so race it against the load state to return early for
+        // `domcontentloaded` while still surfacing navigation errors.
+        let navigate = page.execute(NavigateParams::new(url)).boxed();
+        let step = async_std::future::timeout(self.timeouts().navigation(), async {
+            match future::select(navigate, watch.reached().boxed()).await {
+                Either::Left((Ok(response), reached)) => match response.result.error_text.clone() {
+                    Some(error) => Err(ScrapeError::Navigation {
+                        step: "navigate",
+                        url: url.to_string(),
+                        source: Box::new(CdpError::ChromeMessage(error)),
+                    }),
+                    None => reached.await,
+                },
+                Either::Left((Err(e), _)) => Err(ScrapeError::navigation("navigate", url, e)),
+                Either::Right((reached, _)) => reached,
+            }
+        });
+        match step.await {
+            Ok(Ok(())) => watch.outcome().await,
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err(ScrapeError::NavigationTimeout { step: "navigate", url: url.to_string() }),
+        }
+    }
+
+    /// Open `url` in a new tab and wait until it reaches `until`.
+    pub async fn open_until(&self, url: &str, until: LoadState) -> Result<(Page, Navigation)> {
+        let page = self.new_tab(url).await?;
+        match self.navigate(&page, url, until).await {
+            Ok(navigation) => Ok((page, navigation)),
+            Err(e) => {
+                let _ = page.close().await;
+                Err(e)
+            }
+        }
+    }
+}
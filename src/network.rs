@@ -0,0 +1,182 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use chromiumoxide::cdp::browser_protocol::network::{
+    EventLoadingFailed, EventLoadingFinished, EventRequestWillBeSent, EventResponseReceived, Headers, RequestId,
+    ResourceType,
+};
+use chromiumoxide::cdp::browser_protocol::page::FrameId;
+use chromiumoxide::Page;
+use futures::future::{AbortHandle, Abortable};
+use futures::{stream, StreamExt};
+
+use crate::{Result, ScrapeError};
+
+/// One hop of a main-document redirect chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redirect {
+    /// URL that answered with a redirect.
+    pub from: String,
+    /// URL it redirected to.
+    pub to: String,
+    /// HTTP status of the redirect response, e.g. 301 or 302.
+    pub status: i64,
+}
+
+/// What a [`NetworkTracker`] saw of the main-frame document.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DocumentInfo {
+    /// URL and status of the last document response.
+    pub(crate) response: Option<(String, i64)>,
+    /// Redirects that led to it.
+    pub(crate) redirects: Vec<Redirect>,
+    /// Network error text, if the document failed to load.
+    pub(crate) error: Option<String>,
+}
+
+/// Counts the requests a page has in flight, from CDP Network events, and
+/// records the responses to main-frame document requests.
+///
+/// Only requests started after the tracker was attached are seen, so attach
+/// it before triggering the navigation or action you want to observe.
+/// Listening stops when the tracker is dropped.
+pub(crate) struct NetworkTracker {
+    state: Arc<Mutex<TrackerState>>,
+    abort: AbortHandle,
+}
+
+struct TrackerState {
+    max_inflight: usize,
+    inflight: HashSet<RequestId>,
+    // When the in-flight count last dropped to `max_inflight` or below
+    quiet_since: Option<Instant>,
+    document_request: Option<RequestId>,
+    document: DocumentInfo,
+    // The page closed and no more events will come
+    closed: bool,
+}
+
+enum NetworkEvent {
+    Started(Arc<EventRequestWillBeSent>),
+    Responded(Arc<EventResponseReceived>),
+    Finished(RequestId),
+    Failed(Arc<EventLoadingFailed>),
+}
+
+impl NetworkTracker {
+    /// Start tracking `page`. The network counts as quiet while at most
+    /// `max_inflight` requests are outstanding.
+    pub(crate) async fn attach(page: &Page, max_inflight: usize) -> Result<Self> {
+        let listen = |e| ScrapeError::cdp("track network", "", e);
+        let main_frame = page.mainframe().await.map_err(listen)?;
+        let started = page.event_listener::<EventRequestWillBeSent>().await.map_err(listen)?;
+        let responded = page.event_listener::<EventResponseReceived>().await.map_err(listen)?;
+        let finished = page.event_listener::<EventLoadingFinished>().await.map_err(listen)?;
+        let failed = page.event_listener::<EventLoadingFailed>().await.map_err(listen)?;
+
+        let mut events = stream::select_all([
+            started.map(NetworkEvent::Started).boxed(),
+            responded.map(NetworkEvent::Responded).boxed(),
+            finished.map(|e| NetworkEvent::Finished(e.request_id.clone())).boxed(),
+            failed.map(NetworkEvent::Failed).boxed(),
+        ]);
+
+        let state = Arc::new(Mutex::new(TrackerState {
+            max_inflight,
+            inflight: HashSet::new(),
+            quiet_since: Some(Instant::now()),
+            document_request: None,
+            document: DocumentInfo::default(),
+            closed: false,
+        }));
+        let task_state = Arc::clone(&state);
+        let (abort, registration) = AbortHandle::new_pair();
+        async_std::task::spawn(Abortable::new(
+            async move {
+                while let Some(event) = events.next().await {
+                    task_state.lock().unwrap().record(event, main_frame.as_ref());
+                }
+                task_state.lock().unwrap().closed = true;
+            },
+            registration,
+        ));
+
+        Ok(Self { state, abort })
+    }
+
+    /// How long the network has been quiet, or `None` while it is busy.
+    pub(crate) fn quiet_for(&self) -> Option<Duration> {
+        self.state.lock().unwrap().quiet_since.map(|since| since.elapsed())
+    }
+
+    /// Whether the page has closed.
+    pub(crate) fn is_closed(&self) -> bool {
+        self.state.lock().unwrap().closed
+    }
+
+    /// What has been seen of the current main-frame document.
+    pub(crate) fn document(&self) -> DocumentInfo {
+        self.state.lock().unwrap().document.clone()
+    }
+}
+
+impl TrackerState {
+    fn record(&mut self, event: NetworkEvent, main_frame: Option<&FrameId>) {
+        let is_main_document = |kind: Option<&ResourceType>, frame: Option<&FrameId>| {
+            kind == Some(&ResourceType::Document) && (main_frame.is_none() || frame == main_frame)
+        };
+        match event {
+            NetworkEvent::Started(request) => {
+                if is_main_document(request.r#type.as_ref(), request.frame_id.as_ref()) {
+                    match &request.redirect_response {
+                        Some(response) => self.document.redirects.push(Redirect {
+                            from: response.url.clone(),
+                            to: request.request.url.clone(),
+                            status: response.status,
+                        }),
+                        // A fresh navigation starts a new chain
+                        None => self.document = DocumentInfo::default(),
+                    }
+                    self.document_request = Some(request.request_id.clone());
+                }
+                self.inflight.insert(request.request_id.clone());
+            }
+            NetworkEvent::Responded(response) => {
+                if is_main_document(Some(&response.r#type), response.frame_id.as_ref()) {
+                    self.document.response = Some((response.response.url.clone(), response.response.status));
+                }
+            }
+            NetworkEvent::Finished(id) => {
+                self.inflight.remove(&id);
+            }
+            NetworkEvent::Failed(failure) => {
+                if self.document_request.as_ref() == Some(&failure.request_id) && failure.canceled != Some(true) {
+                    self.document.error = Some(failure.error_text.clone());
+                }
+                self.inflight.remove(&failure.request_id);
+            }
+        }
+        if self.inflight.len() > self.max_inflight {
+            self.quiet_since = None;
+        } else if self.quiet_since.is_none() {
+            self.quiet_since = Some(Instant::now());
+        }
+    }
+}
+
+impl Drop for NetworkTracker {
+    fn drop(&mut self) {
+        self.abort.abort();
+    }
+}
+
+/// Headers from a CDP `Network.Headers` object, as name and value pairs.
+pub(crate) fn header_pairs(headers: &Headers) -> Vec<(String, String)> {
+    let Some(headers) = headers.inner().as_object() else {
+        return Vec::new();
+    };
+    headers
+        .iter()
+        .map(|(name, value)| (name.clone(), value.as_str().map(String::from).unwrap_or_else(|| value.to_string())))
+        .collect()
+}
@@ -0,0 +1,162 @@
+use std::collections::HashSet;
+use chromiumoxide::Page;
+use url::Url;
+
+use crate::scraper::close_page;
+use crate::{normalize_url, Result, Scraper};
+
+/// How to get from one listing page to the next.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NextPage {
+    /// Follow the `href` of the first element matching the selector, such
+    /// as `li.next a`. Pagination ends on the first page without one.
+    Link(String),
+    /// Substitute the page number, starting from 2, for `{page}` in the
+    /// template. Pagination ends on the first page that answers with an
+    /// HTTP error or yields no items.
+    Template(String),
+}
+
+/// Where a listing continues and how many of its pages to read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pagination {
+    pub next: NextPage,
+    /// Stop after this many pages, counting the first.
+    pub max_pages: usize,
+}
+
+impl Pagination {
+    /// Follow next-page links matching `selector`, up to 50 pages.
+    pub fn link(selector: impl Into<String>) -> Self {
+        Self { next: NextPage::Link(selector.into()), max_pages: 50 }
+    }
+
+    /// Fill in page numbers in a URL template, up to 50 pages.
+    pub fn template(template: impl Into<String>) -> Self {
+        Self { next: NextPage::Template(template.into()), max_pages: 50 }
+    }
+
+    /// Read at most `max_pages` pages.
+    pub fn max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = max_pages;
+        self
+    }
+}
+
+/// Why pagination stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaginationEnd {
+    /// The last page of the listing was read.
+    LastPage,
+    /// [`Pagination::max_pages`] pages were read.
+    PageLimit,
+    /// The next page was one already read, e.g. a "next" link pointing back
+    /// to the first page.
+    Loop { url: String },
+}
+
+/// Items aggregated across the pages of a listing.
+#[derive(Debug, Clone)]
+pub struct Paginated<T> {
+    /// Items from every page, in page order.
+    pub items: Vec<T>,
+    /// URLs of the pages read, after redirects.
+    pub pages: Vec<String>,
+    pub end: PaginationEnd,
+}
+
+impl Scraper {
+    /// Read the values of `selector` (as with [`Scraper::extract_from`])
+    /// from every page of the listing starting at `url`, in `page`.
+    pub async fn paginate(
+        &self,
+        page: &Page,
+        url: &str,
+        pagination: &Pagination,
+        selector: &str,
+        attr: Option<&str>,
+    ) -> Result<Paginated<String>> {
+        self.paginate_with(page, url, pagination, |page| async move {
+            self.extract_from(&page, selector, attr).await
+        })
+        .await
+    }
+
+    /// Call `extract` on every page of the listing starting at `url`, in
+    /// `page`, and aggregate the items it returns.
+    pub async fn paginate_with<F, Fut, T>(
+        &self,
+        page: &Page,
+        url: &str,
+        pagination: &Pagination,
+        mut extract: F,
+    ) -> Result<Paginated<T>>
+    where
+        F: FnMut(Page) -> Fut,
+        Fut: Future<Output = Result<Vec<T>>>,
+    {
+        let mut visited = HashSet::new();
+        let mut items = Vec::new();
+        let mut pages = Vec::new();
+        let mut next = url.to_string();
+        let end = loop {
+            if pages.len() >= pagination.max_pages {
+                break PaginationEnd::PageLimit;
+            }
+            if visited.contains(&page_key(&next)) {
+                break PaginationEnd::Loop { url: next };
+            }
+            let navigation = self.goto(page, &next).await?;
+            let (requested, landed) = (page_key(&next), page_key(&navigation.url));
+            // A redirect back to a page already read is a loop too
+            if landed != requested && visited.contains(&landed) {
+                break PaginationEnd::Loop { url: navigation.url };
+            }
+            visited.insert(requested);
+            visited.insert(landed);
+
+            let templated = matches!(pagination.next, NextPage::Template(_));
+            let first = pages.is_empty();
+            if templated && !first && navigation.status.is_some_and(|status| status >= 400) {
+                break PaginationEnd::LastPage;
+            }
+            let found = extract(page.clone()).await?;
+            if templated && !first && found.is_empty() {
+                break PaginationEnd::LastPage;
+            }
+            items.extend(found);
+            pages.push(navigation.url.clone());
+
+            next = match &pagination.next {
+                NextPage::Template(template) => template.replace("{page}", &(pages.len() + 1).to_string()),
+                NextPage::Link(selector) => {
+                    let href = self.extract_from(page, selector, Some("href")).await?.into_iter().next();
+                    match href.and_then(|href| Url::parse(&navigation.url).ok()?.join(&href).ok()) {
+                        Some(link) => link.to_string(),
+                        None => break PaginationEnd::LastPage,
+                    }
+                }
+            };
+        };
+        Ok(Paginated { items, pages, end })
+    }
+
+    /// Open `url` in a new tab and read every page of its listing as with
+    /// [`Scraper::paginate`].
+    pub async fn extract_pages(
+        &self,
+        url: &str,
+        pagination: &Pagination,
+        selector: &str,
+        attr: Option<&str>,
+    ) -> Result<Paginated<String>> {
+        let page = self.new_tab(url).await?;
+        let result = self.paginate(&page, url, pagination, selector, attr).await;
+        close_page(page, result).await
+    }
+}
+
+// Pages are compared by normalized URL so `?a=1&b=2` and `?b=2&a=1` match
+fn page_key(url: &str) -> String {
+    normalize_url(url).map(String::from).unwrap_or_else(|| url.to_string())
+}
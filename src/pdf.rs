@@ -0,0 +1,200 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use chromiumoxide::cdp::browser_protocol::page::PrintToPdfParams;
+use chromiumoxide::Page;
+
+use crate::scraper::{close_page, page_url};
+use crate::{Result, ScrapeError, Scraper};
+
+/// Paper to lay the page out on. Sizes are in inches, portrait.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum PaperSize {
+    #[default]
+    Letter,
+    Legal,
+    Tabloid,
+    A3,
+    A4,
+    A5,
+    Custom { width: f64, height: f64 },
+}
+
+impl PaperSize {
+    /// Width and height in inches.
+    pub fn inches(self) -> (f64, f64) {
+        match self {
+            PaperSize::Letter => (8.5, 11.),
+            PaperSize::Legal => (8.5, 14.),
+            PaperSize::Tabloid => (11., 17.),
+            PaperSize::A3 => (11.69, 16.54),
+            PaperSize::A4 => (8.27, 11.69),
+            PaperSize::A5 => (5.83, 8.27),
+            PaperSize::Custom { width, height } => (width, height),
+        }
+    }
+}
+
+impl FromStr for PaperSize {
+    type Err = String;
+
+    /// A named size such as `a4`, or `WIDTHxHEIGHT` in inches.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "letter" => Ok(PaperSize::Letter),
+            "legal" => Ok(PaperSize::Legal),
+            "tabloid" => Ok(PaperSize::Tabloid),
+            "a3" => Ok(PaperSize::A3),
+            "a4" => Ok(PaperSize::A4),
+            "a5" => Ok(PaperSize::A5),
+            other => other
+                .split_once('x')
+                .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+                .map(|(width, height)| PaperSize::Custom { width, height })
+                .ok_or_else(|| {
+                    format!("unknown paper size `{}` (expected letter, legal, tabloid, a3, a4, a5 or WIDTHxHEIGHT)", s)
+                }),
+        }
+    }
+}
+
+impl fmt::Display for PaperSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaperSize::Letter => f.write_str("letter"),
+            PaperSize::Legal => f.write_str("legal"),
+            PaperSize::Tabloid => f.write_str("tabloid"),
+            PaperSize::A3 => f.write_str("a3"),
+            PaperSize::A4 => f.write_str("a4"),
+            PaperSize::A5 => f.write_str("a5"),
+            PaperSize::Custom { width, height } => write!(f, "{}x{}", width, height),
+        }
+    }
+}
+
+/// Blank space around the printed content, in inches.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Margins {
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+    pub left: f64,
+}
+
+impl Margins {
+    /// The same margin on every side.
+    pub fn uniform(inches: f64) -> Self {
+        Self { top: inches, right: inches, bottom: inches, left: inches }
+    }
+}
+
+impl Default for Margins {
+    /// Chrome's default of 1cm on every side.
+    fn default() -> Self {
+        Self::uniform(0.4)
+    }
+}
+
+/// How to print a page to PDF.
+///
+/// Header and footer templates are HTML; elements with the classes `date`,
+/// `title`, `url`, `pageNumber` and `totalPages` are filled in by Chrome.
+/// They need a margin tall enough to show in, and only use inline styles.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PdfOptions {
+    pub paper: PaperSize,
+    pub margins: Margins,
+    pub landscape: bool,
+    /// Print background colors and images, which browsers leave out by default.
+    pub print_background: bool,
+    pub header_template: Option<String>,
+    pub footer_template: Option<String>,
+    /// Pages to print, one-based, such as `1-5, 8, 11-13`. All pages when unset.
+    pub page_ranges: Option<String>,
+}
+
+impl PdfOptions {
+    pub fn paper(mut self, paper: PaperSize) -> Self {
+        self.paper = paper;
+        self
+    }
+
+    pub fn margins(mut self, margins: Margins) -> Self {
+        self.margins = margins;
+        self
+    }
+
+    pub fn landscape(mut self) -> Self {
+        self.landscape = true;
+        self
+    }
+
+    pub fn print_background(mut self) -> Self {
+        self.print_background = true;
+        self
+    }
+
+    pub fn header_template(mut self, template: impl Into<String>) -> Self {
+        self.header_template = Some(template.into());
+        self
+    }
+
+    pub fn footer_template(mut self, template: impl Into<String>) -> Self {
+        self.footer_template = Some(template.into());
+        self
+    }
+
+    pub fn page_ranges(mut self, ranges: impl Into<String>) -> Self {
+        self.page_ranges = Some(ranges.into());
+        self
+    }
+
+    fn params(&self) -> PrintToPdfParams {
+        let (paper_width, paper_height) = self.paper.inches();
+        let header_footer = self.header_template.is_some() || self.footer_template.is_some();
+        // Chrome prints its own header or footer for a missing template
+        let template = |template: &Option<String>| {
+            header_footer.then(|| template.clone().unwrap_or_else(|| "<span></span>".to_string()))
+        };
+        PrintToPdfParams {
+            landscape: Some(self.landscape),
+            display_header_footer: Some(header_footer),
+            print_background: Some(self.print_background),
+            paper_width: Some(paper_width),
+            paper_height: Some(paper_height),
+            margin_top: Some(self.margins.top),
+            margin_bottom: Some(self.margins.bottom),
+            margin_left: Some(self.margins.left),
+            margin_right: Some(self.margins.right),
+            page_ranges: self.page_ranges.clone(),
+            header_template: template(&self.header_template),
+            footer_template: template(&self.footer_template),
+            ..PrintToPdfParams::default()
+        }
+    }
+}
+
+impl Scraper {
+    /// Print `page` to PDF and return the document.
+    ///
+    /// Only headless Chrome can print.
+    pub async fn pdf(&self, page: &Page, options: &PdfOptions) -> Result<Vec<u8>> {
+        match page.pdf(options.params()).await {
+            Ok(pdf) => Ok(pdf),
+            Err(e) => Err(ScrapeError::cdp("print pdf", page_url(page).await, e)),
+        }
+    }
+
+    /// Print `page` to a PDF file at `path`.
+    pub async fn save_pdf(&self, page: &Page, path: &Path, options: &PdfOptions) -> Result<()> {
+        let pdf = self.pdf(page, options).await?;
+        std::fs::write(path, pdf).map_err(|e| ScrapeError::io("print pdf", path, e))
+    }
+
+    /// Open `url` and print it to a PDF file at `path`.
+    pub async fn print_pdf(&self, url: &str, path: &Path, options: &PdfOptions) -> Result<()> {
+        let page = self.open(url).await?;
+        let result = self.save_pdf(&page, path, options).await;
+        close_page(page, result).await
+    }
+}
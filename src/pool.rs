@@ -0,0 +1,152 @@
+use async_std::channel::{self, Receiver, Sender};
+use chromiumoxide::Page;
+use futures::{Stream, StreamExt};
+
+use crate::{Result, Scraper};
+
+/// Settings for a [`PagePool`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolOptions {
+    /// Maximum number of tabs open, and so of tasks running, at once.
+    pub size: usize,
+    /// Close a tab and open a fresh one after this many tasks. Tabs are
+    /// always reset to `about:blank` between tasks.
+    pub max_uses: usize,
+}
+
+impl Default for PoolOptions {
+    fn default() -> Self {
+        Self { size: 4, max_uses: 20 }
+    }
+}
+
+// An idle tab and how many tasks it has run
+type Slot = Option<(Page, usize)>;
+
+/// A bounded set of browser tabs shared by concurrent tasks.
+///
+/// Each task receives a tab of its own for the duration of the task. Tabs
+/// are reused for later tasks, replaced when they fail or reach
+/// [`PoolOptions::max_uses`], and closed when the pool is closed or dropped.
+pub struct PagePool<'a> {
+    scraper: &'a Scraper,
+    options: PoolOptions,
+    // Holds exactly `options.size` slots; taking one is what bounds concurrency
+    slots: (Sender<Slot>, Receiver<Slot>),
+}
+
+impl<'a> PagePool<'a> {
+    pub fn new(scraper: &'a Scraper, options: PoolOptions) -> Self {
+        let size = options.size.max(1);
+        let (tx, rx) = channel::bounded(size);
+        for _ in 0..size {
+            tx.try_send(None).expect("pool channel has room for every slot");
+        }
+        Self { scraper, options: PoolOptions { size, ..options }, slots: (tx, rx) }
+    }
+
+    pub fn options(&self) -> PoolOptions {
+        self.options
+    }
+
+    /// Run `task` on a pooled tab, waiting for one to become free.
+    pub async fn with_page<F, Fut, T>(&self, task: F) -> Result<T>
+    where
+        F: FnOnce(Page) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let slot = self.slots.1.recv().await.expect("pool keeps its own sender alive");
+        // Gives the slot back even if this future is dropped mid-task
+        let mut checkout = Checkout { sender: &self.slots.0, tab: None, returned: false };
+        let (page, uses) = match slot {
+            Some(idle) => idle,
+            None => (self.scraper.new_tab("about:blank").await?, 0),
+        };
+        checkout.tab = Some(page.clone());
+
+        let result = task(page.clone()).await;
+
+        let uses = uses + 1;
+        let slot = if result.is_ok() && uses < self.options.max_uses && page.goto("about:blank").await.is_ok() {
+            Some((page, uses))
+        } else {
+            let _ = page.close().await;
+            None
+        };
+        checkout.give_back(slot);
+        result
+    }
+
+    /// Run every task with at most [`PoolOptions::size`] in flight,
+    /// yielding results in the order they finish.
+    pub fn run<I, F, Fut, T>(&self, tasks: I) -> impl Stream<Item = Result<T>> + '_
+    where
+        I: IntoIterator<Item = F>,
+        I::IntoIter: 'a,
+        F: FnOnce(Page) -> Fut + 'a,
+        Fut: Future<Output = Result<T>> + 'a,
+        T: 'a,
+    {
+        futures::stream::iter(tasks)
+            .map(move |task| self.with_page(task))
+            .buffer_unordered(self.options.size)
+    }
+
+    /// Close every tab in the pool.
+    pub async fn close(self) {
+        while let Ok(slot) = self.slots.1.try_recv() {
+            if let Some((page, _)) = slot {
+                let _ = page.close().await;
+            }
+        }
+    }
+}
+
+// A slot taken from the pool; returned empty, closing its tab, if dropped
+// before being given back
+struct Checkout<'p> {
+    sender: &'p Sender<Slot>,
+    tab: Option<Page>,
+    returned: bool,
+}
+
+impl Checkout<'_> {
+    fn give_back(mut self, slot: Slot) {
+        // Cannot fail: the pool never holds more than `size` slots
+        let _ = self.sender.try_send(slot);
+        self.returned = true;
+    }
+}
+
+impl Drop for Checkout<'_> {
+    fn drop(&mut self) {
+        if self.returned {
+            return;
+        }
+        if let Some(page) = self.tab.take() {
+            async_std::task::spawn(async move {
+                let _ = page.close().await;
+            });
+        }
+        let _ = self.sender.try_send(None);
+    }
+}
+
+impl Drop for PagePool<'_> {
+    fn drop(&mut self) {
+        while let Ok(slot) = self.slots.1.try_recv() {
+            if let Some((page, _)) = slot {
+                async_std::task::spawn(async move {
+                    let _ = page.close().await;
+                });
+            }
+        }
+    }
+}
+
+impl Scraper {
+    /// A pool of up to `options.size` tabs for running tasks concurrently.
+    pub fn pool(&self, options: PoolOptions) -> PagePool<'_> {
+        PagePool::new(self, options)
+    }
+}
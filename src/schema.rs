@@ -0,0 +1,459 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use chromiumoxide::Page;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use url::Url;
+
+use crate::scraper::{close_page, page_url};
+use crate::{Result, ScrapeError, Scraper};
+
+/// A declarative description of the records on a page.
+///
+/// Every element matching `root` becomes one JSON object with a key per
+/// field. Schemas are usually written as YAML or JSON files:
+///
+/// ```yaml
+/// root: .product_pod
+/// fields:
+///   title: { selector: h3 a, attr: title }
+///   price: { selector: .price_color, type: number }
+///   link: { selector: h3 a, attr: href, type: url }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Schema {
+    /// Selector of the elements that each become one record.
+    pub root: String,
+    pub fields: BTreeMap<String, Field>,
+}
+
+/// One value of a record, read relative to the record's root element.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Field {
+    /// Element to read; the record's root element itself when omitted.
+    pub selector: Option<String>,
+    /// What to read from the element. Defaults to `attr` when [`Field::attr`]
+    /// is set and to `text` otherwise.
+    pub source: Option<Source>,
+    /// Attribute read by the `attr` source.
+    pub attr: Option<String>,
+    /// Keep only the part of the value matching this regex: its first
+    /// capture group if it has one, else the whole match. A value that does
+    /// not match becomes null.
+    pub regex: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: FieldType,
+    /// Read every matching element into an array instead of the first.
+    pub list: bool,
+    /// Fail instead of producing null when the value is missing.
+    pub required: bool,
+    /// Read each matching element as a nested record with these fields.
+    pub fields: Option<BTreeMap<String, Field>>,
+}
+
+/// Where a field's raw value comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    /// The element's trimmed text content.
+    Text,
+    /// The element's inner HTML.
+    Html,
+    /// One of the element's attributes.
+    Attr,
+}
+
+/// The JSON type a field's value is converted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    #[default]
+    String,
+    /// The first number in the text, ignoring currency symbols, thousands
+    /// separators and other text around it (`"£51.77"` becomes `51.77`,
+    /// `"1-2 days"` becomes `1`).
+    Number,
+    /// An absolute URL, resolved against the page's base URL.
+    Url,
+    /// A `YYYY-MM-DD` date, from ISO 8601 dates or dates such as
+    /// `"2 March 2021"` and `"Mar 2, 2021"`.
+    Date,
+}
+
+impl Schema {
+    /// Load a schema from a `.json`, `.yaml` or `.yml` file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path).map_err(|e| ScrapeError::io("load schema", path, e))?;
+        let invalid = |message: String| ScrapeError::Schema(format!("{}: {}", path.display(), message));
+        let schema = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => serde_json::from_str(&contents).map_err(|e| invalid(e.to_string()))?,
+            Some("yaml" | "yml") => serde_yaml::from_str(&contents).map_err(|e| invalid(e.to_string()))?,
+            _ => return Err(invalid("expected a .json, .yaml or .yml file".to_string())),
+        };
+        Self::checked(schema)
+    }
+
+    /// Parse a schema from JSON.
+    pub fn from_json(json: &str) -> Result<Self> {
+        Self::checked(serde_json::from_str(json).map_err(|e| ScrapeError::Schema(e.to_string()))?)
+    }
+
+    /// Parse a schema from YAML.
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        Self::checked(serde_yaml::from_str(yaml).map_err(|e| ScrapeError::Schema(e.to_string()))?)
+    }
+
+    fn checked(schema: Schema) -> Result<Self> {
+        compile(&schema.fields, "")?;
+        Ok(schema)
+    }
+}
+
+// A field ready to apply: its dotted path for errors and compiled regex
+struct Plan<'s> {
+    name: &'s str,
+    path: String,
+    field: &'s Field,
+    regex: Option<Regex>,
+    fields: Option<Vec<Plan<'s>>>,
+}
+
+fn compile<'s>(fields: &'s BTreeMap<String, Field>, parent: &str) -> Result<Vec<Plan<'s>>> {
+    fields
+        .iter()
+        .map(|(name, field)| {
+            let path = if parent.is_empty() { name.clone() } else { format!("{}.{}", parent, name) };
+            let invalid = |message: String| ScrapeError::Schema(format!("field `{}`: {}", path, message));
+            if field.source == Some(Source::Attr) && field.attr.is_none() {
+                return Err(invalid("source `attr` needs an `attr` name".to_string()));
+            }
+            let regex = match &field.regex {
+                Some(pattern) => Some(Regex::new(pattern).map_err(|e| invalid(e.to_string()))?),
+                None => None,
+            };
+            let fields = match &field.fields {
+                Some(fields) => Some(compile(fields, &path)?),
+                None => None,
+            };
+            Ok(Plan { name, path, field, regex, fields })
+        })
+        .collect()
+}
+
+// What the page script returns: the raw strings, shaped like the records
+#[derive(Deserialize)]
+struct RawRecords {
+    base: String,
+    records: Vec<Value>,
+}
+
+// Reads every field's raw value in the page, following the schema's shape
+const EXTRACT_SCRIPT: &str = r#"(schema => {
+    const read = (element, field) => {
+        const source = field.source || (field.attr != null ? 'attr' : 'text');
+        if (source === 'attr') return element.getAttribute(field.attr);
+        if (source === 'html') return element.innerHTML;
+        return element.textContent.trim();
+    };
+    const record = (scope, fields) => Object.fromEntries(Object.entries(fields).map(([name, field]) => {
+        const elements = field.selector ? Array.from(scope.querySelectorAll(field.selector)) : [scope];
+        const value = element => field.fields ? record(element, field.fields) : read(element, field);
+        if (field.list) return [name, elements.map(value)];
+        return [name, elements.length ? value(elements[0]) : null];
+    }));
+    return {
+        base: document.baseURI,
+        records: Array.from(document.querySelectorAll(schema.root)).map(root => record(root, schema.fields)),
+    };
+})"#;
+
+impl Scraper {
+    /// Extract one JSON object per element matching the schema's root.
+    pub async fn extract_records_from(&self, page: &Page, schema: &Schema) -> Result<Vec<Value>> {
+        self.read_records(page, schema, "extract records").await
+    }
+
+    pub(crate) async fn read_records(&self, page: &Page, schema: &Schema, step: &'static str) -> Result<Vec<Value>> {
+        let plans = compile(&schema.fields, "")?;
+        let spec = serde_json::to_string(schema).expect("schemas serialize to JSON");
+        let raw: RawRecords = self.evaluate(page, &format!("{}({})", EXTRACT_SCRIPT, spec)).await?;
+        let url = page_url(page).await;
+        let base = Url::parse(&raw.base).ok();
+        let records = raw.records.into_iter().map(|record| {
+            convert_record(record, &plans, base.as_ref()).map_err(|(field, message)| ScrapeError::Field {
+                step,
+                url: url.clone(),
+                field,
+                message,
+            })
+        });
+        records.collect()
+    }
+
+    /// Open `url` and extract records as with [`Scraper::extract_records_from`].
+    pub async fn extract_records(&self, url: &str, schema: &Schema) -> Result<Vec<Value>> {
+        let page = self.open(url).await?;
+        let result = self.extract_records_from(&page, schema).await;
+        close_page(page, result).await
+    }
+}
+
+// Conversion failures carry the field path and what went wrong
+type Converted = std::result::Result<Value, (String, String)>;
+
+fn convert_record(raw: Value, plans: &[Plan], base: Option<&Url>) -> Converted {
+    let mut raw = match raw {
+        Value::Object(raw) => raw,
+        _ => Map::new(),
+    };
+    let mut record = Map::new();
+    for plan in plans {
+        let value = raw.remove(plan.name).unwrap_or(Value::Null);
+        let value = match value {
+            Value::Array(values) if plan.field.list => {
+                let values = values.into_iter().map(|value| convert_value(value, plan, base));
+                Value::Array(values.collect::<std::result::Result<_, _>>()?)
+            }
+            value => convert_value(value, plan, base)?,
+        };
+        record.insert(plan.name.to_string(), value);
+    }
+    Ok(Value::Object(record))
+}
+
+fn convert_value(raw: Value, plan: &Plan, base: Option<&Url>) -> Converted {
+    let value = match (raw, &plan.fields) {
+        (Value::Null, _) => Value::Null,
+        (raw, Some(fields)) => return convert_record(raw, fields, base),
+        (Value::String(text), None) => convert_text(&text, plan, base)?,
+        (other, None) => other,
+    };
+    if value.is_null() && plan.field.required {
+        return Err((plan.path.clone(), "no value found".to_string()));
+    }
+    Ok(value)
+}
+
+fn convert_text(text: &str, plan: &Plan, base: Option<&Url>) -> Converted {
+    let text = match &plan.regex {
+        Some(regex) => match regex.captures(text) {
+            Some(captures) => captures.get(1).or_else(|| captures.get(0)).map_or("", |m| m.as_str()),
+            None => return Ok(Value::Null),
+        },
+        None => text,
+    };
+    let fail = |kind: &str| Err((plan.path.clone(), format!("`{}` is not a valid {}", text, kind)));
+    match plan.field.kind {
+        FieldType::String => Ok(Value::from(text)),
+        FieldType::Number => match parse_number(text) {
+            Some(number) => Ok(Value::Number(number)),
+            None => fail("number"),
+        },
+        FieldType::Url => match base.map_or_else(|| Url::parse(text), |base| base.join(text)) {
+            Ok(url) => Ok(Value::from(url.to_string())),
+            Err(_) => fail("URL"),
+        },
+        FieldType::Date => match parse_date(text) {
+            Some(date) => Ok(Value::from(date)),
+            None => fail("date"),
+        },
+    }
+}
+
+// The first number in text such as "£1,234.50", "In stock (22 available)" or
+// "Ships in 1-2 days": an optional sign, digits with `,` grouping and at most
+// one decimal point
+pub(crate) fn parse_number(text: &str) -> Option<Number> {
+    let bytes = text.as_bytes();
+    let digit = |i: usize| bytes.get(i).is_some_and(u8::is_ascii_digit);
+    let first = bytes.iter().position(u8::is_ascii_digit)?;
+    let mut start = first;
+    let fraction = start > 0 && bytes[start - 1] == b'.';
+    if fraction {
+        start -= 1;
+    }
+    if start > 0 && matches!(bytes[start - 1], b'-' | b'+') {
+        start -= 1;
+    }
+    let mut end = first;
+    while digit(end) || (bytes.get(end) == Some(&b',') && !fraction && digit(end + 1)) {
+        end += 1;
+    }
+    if !fraction && bytes.get(end) == Some(&b'.') && digit(end + 1) {
+        end += 1;
+        while digit(end) {
+            end += 1;
+        }
+    }
+    let token = text[start..end].replace(',', "");
+    match token.parse::<i64>() {
+        Ok(integer) => Some(Number::from(integer)),
+        Err(_) => Number::from_f64(token.parse().ok()?),
+    }
+}
+
+const MONTHS: [&str; 12] = [
+    "january", "february", "march", "april", "may", "june", "july", "august", "september", "october", "november",
+    "december",
+];
+
+// A YYYY-MM-DD date from ISO 8601, "2 March 2021" or "March 2, 2021"
+fn parse_date(text: &str) -> Option<String> {
+    let text = text.trim();
+    if let Some(iso) = text.get(..10) {
+        let parts: Vec<&str> = iso.split('-').collect();
+        if let [year, month, day] = parts[..] {
+            return format_date(year.parse().ok()?, month.parse().ok()?, day.parse().ok()?);
+        }
+    }
+    let words: Vec<&str> = text.split(|c: char| c.is_whitespace() || c == ',').filter(|w| !w.is_empty()).collect();
+    let month = |word: &str| {
+        let word = word.trim_end_matches('.').to_lowercase();
+        (word.len() >= 3).then(|| MONTHS.iter().position(|month| month.starts_with(&word)))?
+    };
+    let day = |word: &str| word.trim_end_matches(|c: char| c.is_alphabetic()).parse().ok();
+    match words[..] {
+        [d, m, y] if month(m).is_some() => format_date(y.parse().ok()?, month(m)? as u32 + 1, day(d)?),
+        [m, d, y] if month(m).is_some() => format_date(y.parse().ok()?, month(m)? as u32 + 1, day(d)?),
+        _ => None,
+    }
+}
+
+fn format_date(year: i32, month: u32, day: u32) -> Option<String> {
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => return None,
+    };
+    (1..=days).contains(&day).then(|| format!("{:04}-{:02}-{:02}", year, month, day))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const PRODUCTS: &str = "
+root: .product_pod
+fields:
+  title: { selector: h3 a, attr: title, required: true }
+  price: { selector: .price_color, type: number }
+  link: { selector: h3 a, attr: href, type: url }
+  tags: { selector: .tag, list: true }
+  seller:
+    selector: .seller
+    fields:
+      name: { selector: .name }
+      since: { selector: .since, type: date, regex: 'since (.*)' }
+";
+
+    fn convert(schema: &Schema, raw: Value) -> Converted {
+        let plans = compile(&schema.fields, "").unwrap();
+        convert_record(raw, &plans, Url::parse("https://shop.example/catalogue/").ok().as_ref())
+    }
+
+    #[test]
+    fn parses_schemas_from_yaml_and_json() {
+        let schema = Schema::from_yaml(PRODUCTS).unwrap();
+        assert_eq!(schema.root, ".product_pod");
+        assert_eq!(schema.fields["price"].kind, FieldType::Number);
+        assert_eq!(schema.fields["title"].attr.as_deref(), Some("title"));
+        assert!(schema.fields["tags"].list);
+        assert_eq!(schema.fields["seller"].fields.as_ref().unwrap()["since"].kind, FieldType::Date);
+
+        let json = serde_json::to_string(&schema).unwrap();
+        assert_eq!(Schema::from_json(&json).unwrap(), schema);
+    }
+
+    #[test]
+    fn rejects_invalid_schemas() {
+        let message = |result: Result<Schema>| match result {
+            Err(ScrapeError::Schema(message)) => message,
+            other => panic!("expected a schema error, got {:?}", other),
+        };
+        assert!(message(Schema::from_json(r#"{ "root": "li" }"#)).contains("fields"));
+        assert!(message(Schema::from_yaml("root: li\nfields: { a: { colour: red } }")).contains("colour"));
+        assert!(message(Schema::from_yaml("root: li\nfields: { a: { type: money } }")).contains("money"));
+        let attr = message(Schema::from_yaml("root: li\nfields: { a: { fields: { b: { source: attr } } } }"));
+        assert!(attr.starts_with("field `a.b`"), "{}", attr);
+        let regex = message(Schema::from_yaml("root: li\nfields: { a: { regex: '(' } }"));
+        assert!(regex.starts_with("field `a`"), "{}", regex);
+    }
+
+    #[test]
+    fn parses_the_leading_number() {
+        let number = |text| parse_number(text).map(|number| number.to_string());
+        assert_eq!(number("£51.77").as_deref(), Some("51.77"));
+        assert_eq!(number("£1,234.50").as_deref(), Some("1234.5"));
+        assert_eq!(number("In stock (22 available)").as_deref(), Some("22"));
+        assert_eq!(number("Ships in 1-2 days").as_deref(), Some("1"));
+        assert_eq!(number("-3 points").as_deref(), Some("-3"));
+        assert_eq!(number("Save .5%").as_deref(), Some("0.5"));
+        assert_eq!(number("1.2.3").as_deref(), Some("1.2"));
+        assert_eq!(number("1, 2 and 3").as_deref(), Some("1"));
+        assert_eq!(number("4.").as_deref(), Some("4"));
+        assert_eq!(number("12").as_deref(), Some("12"));
+        assert_eq!(number("free"), None);
+    }
+
+    #[test]
+    fn parses_dates() {
+        assert_eq!(parse_date("2021-03-02").as_deref(), Some("2021-03-02"));
+        assert_eq!(parse_date("2021-03-02T10:00:00Z").as_deref(), Some("2021-03-02"));
+        assert_eq!(parse_date("2 March 2021").as_deref(), Some("2021-03-02"));
+        assert_eq!(parse_date("Mar 2, 2021").as_deref(), Some("2021-03-02"));
+        assert_eq!(parse_date("2nd Sept. 2021").as_deref(), Some("2021-09-02"));
+        assert_eq!(parse_date("29 February 2024").as_deref(), Some("2024-02-29"));
+        assert_eq!(parse_date("29 February 2023"), None);
+        assert_eq!(parse_date("2021-13-01"), None);
+        assert_eq!(parse_date("yesterday"), None);
+    }
+
+    #[test]
+    fn converts_raw_values_to_field_types() {
+        let schema = Schema::from_yaml(PRODUCTS).unwrap();
+        let record = convert(
+            &schema,
+            json!({
+                "title": "A Light in the Attic",
+                "price": "£51.77",
+                "link": "a-light-in-the-attic_1000/index.html",
+                "tags": ["poetry", "classics"],
+                "seller": { "name": "Books Ltd", "since": "since 2 March 2021" },
+            }),
+        )
+        .unwrap();
+        assert_eq!(
+            record,
+            json!({
+                "title": "A Light in the Attic",
+                "price": 51.77,
+                "link": "https://shop.example/catalogue/a-light-in-the-attic_1000/index.html",
+                "tags": ["poetry", "classics"],
+                "seller": { "name": "Books Ltd", "since": "2021-03-02" },
+            })
+        );
+
+        let record = convert(&schema, json!({ "title": "Untitled", "seller": { "since": "recently" } })).unwrap();
+        assert_eq!(record["price"], Value::Null);
+        assert_eq!(record["seller"]["since"], Value::Null);
+    }
+
+    #[test]
+    fn reports_the_field_that_fails_to_convert() {
+        let schema = Schema::from_yaml(PRODUCTS).unwrap();
+        let error = convert(&schema, json!({ "title": "A", "price": "free" })).unwrap_err();
+        assert_eq!(error, ("price".to_string(), "`free` is not a valid number".to_string()));
+        let error = convert(&schema, json!({ "title": "A", "seller": { "since": "since Smarch" } })).unwrap_err();
+        assert_eq!(error.0, "seller.since");
+        let error = convert(&schema, json!({ "price": "1" })).unwrap_err();
+        assert_eq!(error, ("title".to_string(), "no value found".to_string()));
+    }
+}
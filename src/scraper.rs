@@ -0,0 +1,271 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use async_std::task::JoinHandle;
+use chromiumoxide::cdp::browser_protocol::target::TargetId;
+use chromiumoxide::{Browser, BrowserConfig, Element, Page};
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+
+use crate::console::ConsoleSession;
+use crate::har::HarSession;
+use crate::network::NetworkTracker;
+use crate::sites::wikipedia::{SearchOutcome, Wikipedia};
+use crate::{
+    Emulation, Interceptor, LoadState, Navigation, RequestRules, Result, ScrapeError, ScraperConfig, ScreenshotOptions,
+    Timeouts, TypeOptions, WaitFor,
+};
+
+/// A running browser session.
+///
+/// Owns the [`Browser`], the task polling its event handler, and the logic to
+/// shut both down. Call [`Scraper::close`] when done; dropping a `Scraper`
+/// kills the browser process without a graceful shutdown.
+pub struct Scraper {
+    browser: Browser,
+    handle: JoinHandle<()>,
+    timeouts: Timeouts,
+    load_state: LoadState,
+    pub(crate) emulation: Option<Emulation>,
+    pub(crate) request_rules: Option<RequestRules>,
+    // Interceptors of open tabs, for `request_counts`
+    pub(crate) interceptors: Mutex<Vec<(TargetId, Interceptor)>>,
+    // Requests of the tabs the helpers opened, for `WaitFor::NetworkIdle`
+    pub(crate) network_trackers: Mutex<Vec<(TargetId, Arc<NetworkTracker>)>>,
+    pub(crate) har: Option<HarSession>,
+    pub(crate) console: Option<ConsoleSession>,
+}
+
+/// Where and how to find the search box of a MediaWiki-style site.
+#[derive(Debug, Clone)]
+pub struct SearchForm {
+    /// Page to open before searching.
+    pub url: String,
+    /// Selector of the element that reveals the search box.
+    pub toggle_selector: String,
+    /// Selector of the search input field.
+    pub input_selector: String,
+    /// Pages of hits to read when the search lists results.
+    pub result_pages: usize,
+}
+
+impl Default for SearchForm {
+    fn default() -> Self {
+        Self {
+            url: "https://en.wikipedia.org".to_string(),
+            toggle_selector: "#p-search > a".to_string(),
+            input_selector: "input[name='search']".to_string(),
+            result_pages: 1,
+        }
+    }
+}
+
+impl Scraper {
+    /// Launch a browser from declarative settings, see [`ScraperConfig`].
+    pub async fn from_config(config: &ScraperConfig) -> Result<Self> {
+        let mut scraper = Self::launch(config.browser_config()?).await?;
+        scraper.timeouts = config.timeouts;
+        Ok(scraper)
+    }
+
+    /// Launch a browser with `config` and start driving its handler.
+    ///
+    /// Uses the default [`Timeouts`] for navigation.
+    pub async fn launch(config: BrowserConfig) -> Result<Self> {
+        let (browser, mut handler) = Browser::launch(config)
+            .await
+            .map_err(|source| ScrapeError::Launch { source: Box::new(source) })?;
+
+        // Run the browser handler in a separate task
+        let handle = async_std::task::spawn(async move {
+            while let Some(_event) = handler.next().await {}
+        });
+
+        Ok(Self {
+            browser,
+            handle,
+            timeouts: Timeouts::default(),
+            load_state: LoadState::default(),
+            emulation: None,
+            request_rules: None,
+            interceptors: Mutex::default(),
+            network_trackers: Mutex::default(),
+            har: None,
+            console: None,
+        })
+    }
+
+    /// The timeouts this session applies.
+    pub fn timeouts(&self) -> &Timeouts {
+        &self.timeouts
+    }
+
+    /// The load state [`Scraper::open`] and the other helpers wait for.
+    pub fn load_state(&self) -> LoadState {
+        self.load_state
+    }
+
+    /// Change the load state the helpers wait for after navigating.
+    pub fn set_load_state(&mut self, load_state: LoadState) {
+        self.load_state = load_state;
+    }
+
+    /// The underlying browser, for anything not covered by the helpers.
+    pub fn browser(&self) -> &Browser {
+        &self.browser
+    }
+
+    /// Close the browser and wait for the handler task to finish, and for
+    /// the session's HAR files to be written.
+    pub async fn close(mut self) -> Result<()> {
+        self.browser
+            .close()
+            .await
+            .map_err(|source| ScrapeError::BrowserClosed { step: "close", source: Box::new(source) })?;
+        self.handle.await;
+        match self.har.take() {
+            Some(har) => har.finish().await,
+            None => Ok(()),
+        }
+    }
+
+    /// Open `url` in a new tab and wait for the session's load state.
+    pub async fn open(&self, url: &str) -> Result<Page> {
+        Ok(self.open_until(url, self.load_state).await?.0)
+    }
+
+    /// Navigate an existing tab to `url` and wait for the session's load state.
+    pub async fn goto(&self, page: &Page, url: &str) -> Result<Navigation> {
+        self.navigate(page, url, self.load_state).await
+    }
+
+    // A blank tab with its requests tracked and the session's emulation,
+    // request rules, HAR recording and console collection applied, to
+    // navigate to `url`
+    pub(crate) async fn new_tab(&self, url: &str) -> Result<Page> {
+        let page = self
+            .browser
+            .new_page("about:blank")
+            .await
+            .map_err(|e| ScrapeError::navigation("open", url, e))?;
+        let prepared = async {
+            self.track_network(&page).await?;
+            if let Some(emulation) = &self.emulation {
+                self.emulate(&page, emulation).await?;
+            }
+            self.intercept_session(&page).await?;
+            self.record_har_session(&page).await?;
+            self.collect_console_session(&page).await?;
+            Ok(())
+        }
+        .await;
+        match prepared {
+            Ok(()) => Ok(page),
+            Err(e) => {
+                let _ = page.close().await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Find the first element matching `selector`.
+    pub async fn find(&self, page: &Page, selector: &str) -> Result<Element> {
+        match page.find_element(selector).await {
+            Ok(element) => Ok(element),
+            Err(e) => Err(ScrapeError::selector("find", page_url(page).await, selector, e)),
+        }
+    }
+
+    /// Evaluate a JavaScript expression and deserialize its value.
+    pub async fn evaluate<T: DeserializeOwned>(&self, page: &Page, expression: &str) -> Result<T> {
+        let result = match page.evaluate(expression).await {
+            Ok(result) => result,
+            Err(e) => return Err(ScrapeError::cdp("evaluate", page_url(page).await, e)),
+        };
+        match result.into_value() {
+            Ok(value) => Ok(value),
+            Err(e) => Err(ScrapeError::deserialize("evaluate", page_url(page).await, e)),
+        }
+    }
+
+    /// Read an attribute (or the text, when `attr` is `None`) of every
+    /// element matching `selector`. Elements lacking the attribute are skipped.
+    pub async fn extract_from(&self, page: &Page, selector: &str, attr: Option<&str>) -> Result<Vec<String>> {
+        let read = match attr {
+            Some(attr) => format!("element.getAttribute({})", js_string(attr)),
+            None => "element.textContent.trim()".to_string(),
+        };
+        let expression = format!(
+            "Array.from(document.querySelectorAll({})).map(element => {}).filter(value => value !== null)",
+            js_string(selector),
+            read
+        );
+        self.evaluate(page, &expression).await
+    }
+
+    /// Capture a viewport screenshot of `page` as PNG into `path`. See
+    /// [`Scraper::save_screenshot`] for other regions and formats.
+    pub async fn capture(&self, page: &Page, path: &Path) -> Result<()> {
+        self.save_screenshot(page, path, &ScreenshotOptions::default()).await
+    }
+
+    /// Search a MediaWiki site through its search box and report where the
+    /// search leads: an article, a list of results, or a disambiguation page.
+    pub async fn search(&self, form: &SearchForm, term: &str) -> Result<SearchOutcome> {
+        let page = self.open(&form.url).await?;
+        let result = self.search_on(&page, form, term).await;
+        close_page(page, result).await
+    }
+
+    async fn search_on(&self, page: &Page, form: &SearchForm, term: &str) -> Result<SearchOutcome> {
+        let wiki = Wikipedia::with_base_url(self, &form.url)?.result_pages(form.result_pages);
+        let step = |e| ScrapeError::cdp("search", form.url.as_str(), e);
+
+        // Find and click on the search button to reveal the input field
+        self.find(page, &form.toggle_selector).await?.click().await.map_err(step)?;
+
+        // Type the term and submit with a real Enter key press, so the site's
+        // own key handlers run
+        self.type_into(page, &form.input_selector, term, TypeOptions::default()).await?;
+        let navigation = self.watch_navigation(page, self.load_state).await?;
+        self.press(page, "Enter").await?;
+
+        // Wait for the search results page to load
+        navigation.wait().await?;
+
+        wiki.outcome(page).await
+    }
+
+    /// Open `url`, wait for `ready` to hold, and save a viewport PNG
+    /// screenshot to `path`.
+    pub async fn take_screenshot(&self, url: &str, path: &Path, ready: &WaitFor) -> Result<()> {
+        self.take_screenshot_with(url, path, ready, &ScreenshotOptions::default()).await
+    }
+
+    /// Open `url` and extract values as with [`Scraper::extract_from`].
+    pub async fn extract(&self, url: &str, selector: &str, attr: Option<&str>) -> Result<Vec<String>> {
+        let page = self.open(url).await?;
+        let result = self.extract_from(&page, selector, attr).await;
+        close_page(page, result).await
+    }
+}
+
+// Close a tab opened by one of the helpers once `result` is known. The
+// operation's own error wins over a failure to close.
+pub(crate) async fn close_page<T>(page: Page, result: Result<T>) -> Result<T> {
+    let closed = page.close().await;
+    let value = result?;
+    match closed {
+        Ok(_) => Ok(value),
+        Err(source) => Err(ScrapeError::BrowserClosed { step: "close page", source: Box::new(source) }),
+    }
+}
+
+// Best-effort URL of a page, for error context
+pub(crate) async fn page_url(page: &Page) -> String {
+    page.url().await.ok().flatten().unwrap_or_default()
+}
+
+// Quote a string as a JavaScript string literal
+pub(crate) fn js_string(value: &str) -> String {
+    serde_json::Value::from(value).to_string()
+}
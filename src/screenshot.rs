@@ -0,0 +1,248 @@
+use std::path::Path;
+use std::str::FromStr;
+use chromiumoxide::cdp::browser_protocol::dom::Rgba;
+use chromiumoxide::cdp::browser_protocol::emulation::SetDefaultBackgroundColorOverrideParams;
+use chromiumoxide::cdp::browser_protocol::page::{CaptureScreenshotFormat, CaptureScreenshotParams, Viewport};
+use chromiumoxide::page::ScreenshotParams;
+use chromiumoxide::Page;
+
+use crate::scraper::{close_page, js_string, page_url};
+use crate::{Result, ScrapeError, Scraper, WaitFor};
+
+/// How a screenshot is encoded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ImageFormat {
+    #[default]
+    Png,
+    Jpeg,
+    Webp,
+}
+
+impl ImageFormat {
+    /// The format a file name's extension stands for, if any.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()?.to_str()?.to_ascii_lowercase().parse().ok()
+    }
+
+    /// The usual file name extension for the format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Jpeg => "jpg",
+            ImageFormat::Webp => "webp",
+        }
+    }
+}
+
+impl FromStr for ImageFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "png" => Ok(ImageFormat::Png),
+            "jpeg" | "jpg" => Ok(ImageFormat::Jpeg),
+            "webp" => Ok(ImageFormat::Webp),
+            _ => Err(format!("unknown image format `{}` (expected png, jpeg or webp)", s)),
+        }
+    }
+}
+
+/// A rectangle of the page, in CSS pixels from the top left of the document.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Clip {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// The part of a page a screenshot shows.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Region {
+    /// What is currently scrolled into view.
+    #[default]
+    Viewport,
+    /// The whole document, however far it scrolls.
+    FullPage,
+    /// The box of the first element matching the selector.
+    Element(String),
+    Clip(Clip),
+}
+
+/// What to capture and how to encode it.
+///
+/// ```ignore
+/// let options = ScreenshotOptions::full_page().format(ImageFormat::Jpeg).quality(80);
+/// let jpeg = scraper.screenshot(&page, &options).await?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScreenshotOptions {
+    pub region: Region,
+    pub format: ImageFormat,
+    /// Compression quality from 0 to 100. Ignored for PNG, which is lossless.
+    pub quality: Option<u8>,
+    /// Image pixels per CSS pixel, e.g. 2 for a high-density capture.
+    /// Defaults to the browser's own device scale factor.
+    pub device_scale_factor: Option<f64>,
+    /// Leave the page's default white background transparent. Ignored for
+    /// JPEG, which has no alpha channel.
+    pub transparent: bool,
+}
+
+impl ScreenshotOptions {
+    /// Capture the whole document, measuring how far it scrolls.
+    pub fn full_page() -> Self {
+        Self { region: Region::FullPage, ..Self::default() }
+    }
+
+    /// Capture the first element matching `selector`.
+    pub fn element(selector: impl Into<String>) -> Self {
+        Self { region: Region::Element(selector.into()), ..Self::default() }
+    }
+
+    /// Capture the given rectangle of the document.
+    pub fn clip(clip: Clip) -> Self {
+        Self { region: Region::Clip(clip), ..Self::default() }
+    }
+
+    pub fn format(mut self, format: ImageFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn quality(mut self, quality: u8) -> Self {
+        self.quality = Some(quality.min(100));
+        self
+    }
+
+    pub fn device_scale_factor(mut self, factor: f64) -> Self {
+        self.device_scale_factor = Some(factor);
+        self
+    }
+
+    pub fn transparent(mut self) -> Self {
+        self.transparent = true;
+        self
+    }
+}
+
+// Document rectangles of each region, as [x, y, width, height]
+const VIEWPORT_SCRIPT: &str =
+    "[scrollX, scrollY, document.documentElement.clientWidth, document.documentElement.clientHeight]";
+const FULL_PAGE_SCRIPT: &str = r#"(() => {
+    const root = document.documentElement;
+    const body = document.body || root;
+    return [0, 0, Math.max(root.scrollWidth, body.scrollWidth), Math.max(root.scrollHeight, body.scrollHeight)];
+})()"#;
+
+impl Scraper {
+    /// Capture `page` as described by `options` and return the encoded image.
+    pub async fn screenshot(&self, page: &Page, options: &ScreenshotOptions) -> Result<Vec<u8>> {
+        let clip = self.clip_for(page, options).await?;
+        let format = match options.format {
+            ImageFormat::Png => CaptureScreenshotFormat::Png,
+            ImageFormat::Jpeg => CaptureScreenshotFormat::Jpeg,
+            ImageFormat::Webp => CaptureScreenshotFormat::Webp,
+        };
+        let params = CaptureScreenshotParams {
+            quality: options.quality.filter(|_| options.format != ImageFormat::Png).map(i64::from),
+            format: Some(format),
+            capture_beyond_viewport: Some(options.region != Region::Viewport),
+            clip,
+            ..CaptureScreenshotParams::default()
+        };
+
+        let transparent = options.transparent && options.format != ImageFormat::Jpeg;
+        if transparent {
+            let clear = Rgba { r: 0, g: 0, b: 0, a: Some(0.) };
+            set_background(page, Some(clear)).await?;
+        }
+        let captured = match page.screenshot(ScreenshotParams::from(params)).await {
+            Ok(image) => Ok(image),
+            Err(e) => Err(ScrapeError::cdp("screenshot", page_url(page).await, e)),
+        };
+        // The capture's own error wins over a failure to restore
+        let restored = if transparent { set_background(page, None).await } else { Ok(()) };
+        let image = captured?;
+        restored?;
+        Ok(image)
+    }
+
+    /// Capture `page` as described by `options` into `path`.
+    pub async fn save_screenshot(&self, page: &Page, path: &Path, options: &ScreenshotOptions) -> Result<()> {
+        let image = self.screenshot(page, options).await?;
+        std::fs::write(path, image).map_err(|e| ScrapeError::io("screenshot", path, e))
+    }
+
+    /// Open `url`, wait for `ready` to hold, and save a screenshot taken
+    /// with `options` to `path`.
+    pub async fn take_screenshot_with(
+        &self,
+        url: &str,
+        path: &Path,
+        ready: &WaitFor,
+        options: &ScreenshotOptions,
+    ) -> Result<()> {
+        let page = self.open(url).await?;
+        let result = match self.wait(&page, ready).await {
+            Ok(()) => self.save_screenshot(&page, path, options).await,
+            Err(e) => Err(e),
+        };
+        close_page(page, result).await
+    }
+
+    // The area to capture, or None to let the browser capture the viewport
+    async fn clip_for(&self, page: &Page, options: &ScreenshotOptions) -> Result<Option<Viewport>> {
+        if options.region == Region::Viewport && options.device_scale_factor.is_none() {
+            return Ok(None);
+        }
+        let Clip { x, y, width, height } = self.region_rect(page, &options.region).await?;
+        // The clip scale multiplies the browser's own device pixel ratio
+        let scale = match options.device_scale_factor {
+            Some(factor) => factor / self.evaluate::<f64>(page, "devicePixelRatio").await?,
+            None => 1.,
+        };
+        Ok(Some(Viewport { x, y, width, height, scale }))
+    }
+
+    // The document rectangle `region` covers. Elements are scrolled into view.
+    pub(crate) async fn region_rect(&self, page: &Page, region: &Region) -> Result<Clip> {
+        let [x, y, width, height] = match region {
+            Region::Viewport => self.evaluate(page, VIEWPORT_SCRIPT).await?,
+            Region::FullPage => self.evaluate(page, FULL_PAGE_SCRIPT).await?,
+            Region::Element(selector) => {
+                let script = format!(
+                    r#"(() => {{
+                        const element = document.querySelector({});
+                        if (!element) return null;
+                        element.scrollIntoView({{ block: 'nearest', inline: 'nearest' }});
+                        const rect = element.getBoundingClientRect();
+                        if (rect.width === 0 || rect.height === 0) return null;
+                        return [rect.left + scrollX, rect.top + scrollY, rect.width, rect.height];
+                    }})()"#,
+                    js_string(selector)
+                );
+                match self.evaluate::<Option<[f64; 4]>>(page, &script).await? {
+                    Some(rect) => rect,
+                    None => {
+                        return Err(ScrapeError::NotFound {
+                            step: "screenshot",
+                            url: page_url(page).await,
+                            what: format!("a visible element matching `{}`", selector),
+                        });
+                    }
+                }
+            }
+            Region::Clip(clip) => return Ok(*clip),
+        };
+        Ok(Clip { x, y, width, height })
+    }
+}
+
+// Override the page's default background, or restore it with None
+async fn set_background(page: &Page, color: Option<Rgba>) -> Result<()> {
+    match page.execute(SetDefaultBackgroundColorOverrideParams { color }).await {
+        Ok(_) => Ok(()),
+        Err(e) => Err(ScrapeError::cdp("screenshot", page_url(page).await, e)),
+    }
+}
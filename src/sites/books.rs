@@ -0,0 +1,217 @@
+//! [books.toscrape.com](https://books.toscrape.com), a catalogue built for
+//! scraping practice and our reference target for validating changes.
+
+use std::collections::HashSet;
+use chromiumoxide::Page;
+use futures::TryStreamExt;
+use serde::Serialize;
+
+use crate::scraper::{close_page, page_url};
+use crate::{Pagination, PoolOptions, Result, Scrape, ScrapeError, Scraper};
+
+/// Where the live site is served.
+pub const BASE_URL: &str = "https://books.toscrape.com/";
+
+/// A category from the home page's sidebar.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Category {
+    pub name: String,
+    /// URL of the category's first listing page.
+    pub url: String,
+}
+
+/// Everything a product detail page says about a book.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Book {
+    pub title: String,
+    /// Price excluding tax, in pounds.
+    pub price: f64,
+    /// Price including tax, in pounds.
+    pub price_incl_tax: f64,
+    pub tax: f64,
+    /// Copies in stock.
+    pub availability: u32,
+    /// Star rating, from 1 to 5.
+    pub rating: u8,
+    pub upc: String,
+    pub description: Option<String>,
+    pub category: String,
+    pub image_url: String,
+    /// URL of the detail page.
+    pub url: String,
+}
+
+// The detail page as the site lays it out
+#[derive(Scrape)]
+struct ProductPage {
+    #[scrape(selector = ".product_main h1")]
+    title: String,
+    #[scrape(selector = ".product_page table tr:nth-child(3) td", parse = "price")]
+    price: f64,
+    #[scrape(selector = ".product_page table tr:nth-child(4) td", parse = "price")]
+    price_incl_tax: f64,
+    #[scrape(selector = ".product_page table tr:nth-child(5) td", parse = "price")]
+    tax: f64,
+    #[scrape(selector = ".product_main .availability", regex = "\\((\\d+) available\\)")]
+    availability: u32,
+    #[scrape(selector = ".product_main .star-rating", attr = "class", regex = "star-rating (\\w+)")]
+    rating: String,
+    #[scrape(selector = ".product_page table tr:nth-child(1) td")]
+    upc: String,
+    #[scrape(selector = "#product_description ~ p")]
+    description: Option<String>,
+    #[scrape(selector = ".breadcrumb li:nth-child(3) a")]
+    category: String,
+    #[scrape(selector = "#product_gallery img", attr = "src", parse = "url")]
+    image_url: String,
+}
+
+/// Scrapes the books.toscrape.com catalogue: categories, their paginated
+/// listings, and the detail page of every book.
+pub struct BooksToScrape<'a> {
+    scraper: &'a Scraper,
+    base_url: String,
+    concurrency: usize,
+}
+
+impl<'a> BooksToScrape<'a> {
+    /// An adapter for the live site.
+    pub fn new(scraper: &'a Scraper) -> Self {
+        Self::with_base_url(scraper, BASE_URL)
+    }
+
+    /// An adapter for a copy of the site served from `base_url`, such as
+    /// saved fixtures.
+    pub fn with_base_url(scraper: &'a Scraper, base_url: impl Into<String>) -> Self {
+        let mut base_url = base_url.into();
+        if !base_url.ends_with('/') {
+            base_url.push('/');
+        }
+        Self { scraper, base_url, concurrency: 4 }
+    }
+
+    /// Scrape up to `concurrency` pages at once.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Every category listed on the home page.
+    pub async fn categories(&self) -> Result<Vec<Category>> {
+        let page = self.scraper.open(&self.base_url).await?;
+        let categories = self.categories_on(&page).await;
+        close_page(page, categories).await
+    }
+
+    async fn categories_on(&self, page: &Page) -> Result<Vec<Category>> {
+        let links: Vec<(String, String)> = self
+            .scraper
+            .evaluate(
+                page,
+                "Array.from(document.querySelectorAll('.side_categories ul ul a'))\
+                 .map(link => [link.textContent.trim(), link.href])",
+            )
+            .await?;
+        Ok(links.into_iter().map(|(name, url)| Category { name, url }).collect())
+    }
+
+    /// Detail page URLs of every book in `category`, across all its pages.
+    pub async fn book_urls(&self, page: &Page, category: &Category) -> Result<Vec<String>> {
+        let pagination = Pagination::link("li.next a");
+        let listing = self
+            .scraper
+            .paginate_with(page, &category.url, &pagination, |page| async move {
+                self.scraper
+                    .evaluate::<Vec<String>>(
+                        &page,
+                        "Array.from(document.querySelectorAll('.product_pod h3 a')).map(link => link.href)",
+                    )
+                    .await
+            })
+            .await?;
+        Ok(listing.items)
+    }
+
+    /// Read the detail page at `url` in `page`.
+    pub async fn book(&self, page: &Page, url: &str) -> Result<Book> {
+        self.scraper.goto(page, url).await?;
+        // The whole page is one record
+        let product = match self.scraper.extract_all::<ProductPage>(page, "body").await?.pop() {
+            Some(product) => product,
+            None => {
+                let what = "document body".to_string();
+                return Err(ScrapeError::NotFound { step: "book", url: page_url(page).await, what });
+            }
+        };
+        let rating = match stars(&product.rating) {
+            Some(rating) => rating,
+            None => {
+                return Err(ScrapeError::Field {
+                    step: "book",
+                    url: page_url(page).await,
+                    field: "rating".to_string(),
+                    message: format!("unknown star rating `{}`", product.rating),
+                });
+            }
+        };
+        Ok(Book {
+            title: product.title,
+            price: product.price,
+            price_incl_tax: product.price_incl_tax,
+            tax: product.tax,
+            availability: product.availability,
+            rating,
+            upc: product.upc,
+            description: product.description,
+            category: product.category,
+            image_url: product.image_url,
+            url: url.to_string(),
+        })
+    }
+
+    /// Every book in `categories`, in category and listing order.
+    ///
+    /// A detail page that fails to load or read is reported in its place
+    /// and does not stop the walk; only failing to list a category or the
+    /// browser itself does.
+    pub async fn books_in(&self, categories: &[Category]) -> Result<Vec<Result<Book>>> {
+        let pool = self.scraper.pool(PoolOptions { size: self.concurrency, ..PoolOptions::default() });
+
+        let listings = categories.iter().enumerate().map(|(index, category)| {
+            move |page: Page| async move { Ok((index, self.book_urls(&page, category).await?)) }
+        });
+        let mut listings: Vec<(usize, Vec<String>)> = pool.run(listings).try_collect().await?;
+        listings.sort_by_key(|(index, _)| *index);
+
+        // A book listed twice is only visited once
+        let mut seen = HashSet::new();
+        let urls: Vec<String> =
+            listings.into_iter().flat_map(|(_, urls)| urls).filter(|url| seen.insert(url.clone())).collect();
+
+        let details = urls.iter().enumerate().map(|(index, url)| {
+            move |page: Page| async move { Ok((index, self.book(&page, url).await)) }
+        });
+        let mut books: Vec<(usize, Result<Book>)> = pool.run(details).try_collect().await?;
+        pool.close().await;
+        books.sort_by_key(|(index, _)| *index);
+        Ok(books.into_iter().map(|(_, book)| book).collect())
+    }
+
+    /// Every book in the catalogue, as with [`BooksToScrape::books_in`].
+    pub async fn catalogue(&self) -> Result<Vec<Result<Book>>> {
+        let categories = self.categories().await?;
+        self.books_in(&categories).await
+    }
+}
+
+// The site spells ratings out as a class: "star-rating Three"
+fn stars(rating: &str) -> Option<u8> {
+    match rating {
+        "One" => Some(1),
+        "Two" => Some(2),
+        "Three" => Some(3),
+        "Four" => Some(4),
+        "Five" => Some(5),
+        _ => None,
+    }
+}
@@ -0,0 +1,4 @@
+//! Adapters for specific sites, built on the generic [`Scraper`](crate::Scraper) helpers.
+
+pub mod books;
+pub mod wikipedia;
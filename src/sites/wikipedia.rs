@@ -0,0 +1,423 @@
+//! Wikipedia, and other MediaWiki sites with the same skin: search and
+//! structured article extraction.
+
+use chromiumoxide::Page;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::scraper::{close_page, page_url};
+use crate::{Pagination, Result, ScrapeError, Scraper};
+
+/// Where the live English Wikipedia is served.
+pub const BASE_URL: &str = "https://en.wikipedia.org/";
+
+/// The structured contents of an article.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Article {
+    pub title: String,
+    /// URL of the article, after redirects.
+    pub url: String,
+    /// The paragraphs before the first heading, separated by blank lines.
+    pub lead: String,
+    /// Top-level sections, with their subsections nested.
+    pub sections: Vec<Section>,
+    /// Label/value rows of the infobox, in page order.
+    pub infobox: Vec<(String, String)>,
+    pub categories: Vec<String>,
+    /// Links to other articles from the body, without duplicates.
+    pub links: Vec<Link>,
+    /// Text of each reference, in numbering order.
+    pub references: Vec<String>,
+}
+
+/// A headed section of an article.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Section {
+    pub title: String,
+    /// Heading level: 2 for top-level sections, 3 for their subsections...
+    pub level: u8,
+    /// Fragment identifier linking to the heading.
+    pub anchor: String,
+    /// The section's own paragraphs, separated by blank lines.
+    pub text: String,
+    pub subsections: Vec<Section>,
+}
+
+/// A link to another article.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Link {
+    pub title: String,
+    pub url: String,
+}
+
+/// Where a search for a term leads.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum SearchOutcome {
+    /// The term named an article, which the wiki went straight to.
+    Article(Article),
+    /// No article matched exactly, so the wiki listed search results.
+    Results(Vec<Hit>),
+    /// The term names several things, listed on a disambiguation page.
+    Disambiguation(Vec<DisambiguationOption>),
+}
+
+/// One search result.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hit {
+    pub title: String,
+    pub url: String,
+    /// The excerpt shown under the title.
+    pub snippet: String,
+    /// Size of the article as the wiki shows it, such as "120 KB".
+    pub size: Option<String>,
+    pub words: Option<u64>,
+}
+
+/// One of the meanings listed on a disambiguation page.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisambiguationOption {
+    /// Title of the article the entry links to first.
+    pub title: String,
+    pub url: String,
+    /// The entry's whole text, such as "Rust (band), a Danish rock band".
+    pub description: String,
+    /// The heading the entry is listed under, if any.
+    pub section: Option<String>,
+}
+
+// What kind of page a search landed on
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum Landing {
+    Article,
+    Results { next: Option<String> },
+    Disambiguation { options: Vec<DisambiguationOption> },
+}
+
+// What the page script returns, before sections are nested
+#[derive(Deserialize)]
+struct RawArticle {
+    title: String,
+    url: String,
+    lead: Vec<String>,
+    sections: Vec<RawSection>,
+    infobox: Vec<(String, String)>,
+    categories: Vec<String>,
+    links: Vec<Link>,
+    references: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct RawSection {
+    title: String,
+    level: u8,
+    anchor: String,
+    paragraphs: Vec<String>,
+}
+
+// Definitions shared by the page scripts, see `in_page`
+const HELPERS: &str = r#"
+    const content = document.querySelector('#mw-content-text .mw-parser-output');
+    // Text with whitespace collapsed; line breaks and list items become
+    // separate lines, as in infobox values
+    const clean = node => {
+        const copy = node.cloneNode(true);
+        copy.querySelectorAll('sup.reference, .mw-editsection, style, .noprint').forEach(extra => extra.remove());
+        copy.querySelectorAll('br').forEach(br => br.replaceWith('\u241E'));
+        copy.querySelectorAll('li').forEach(item => item.append('\u241E'));
+        return copy.textContent.split('\u241E').map(line => line.replace(/\s+/g, ' ').trim()).filter(Boolean).join('\n');
+    };
+    // Handles both heading markups: `<h2><span class="mw-headline">` and
+    // `<div class="mw-heading"><h2>`
+    const headingOf = node => {
+        if (/^H[2-6]$/.test(node.tagName)) return node;
+        if (node.classList.contains('mw-heading')) return node.querySelector('h2, h3, h4, h5, h6');
+        return null;
+    };
+    const headlineOf = heading => heading.querySelector('.mw-headline') || heading;
+    // Title of the article a link points to, or null for links elsewhere:
+    // other sites, or namespaces such as `File:`
+    const articleTitle = link => {
+        const url = new URL(link.href);
+        if (url.origin !== location.origin || !url.pathname.startsWith('/wiki/')) return null;
+        const name = decodeURIComponent(url.pathname.slice('/wiki/'.length));
+        if (name.includes(':')) return null;
+        return link.getAttribute('title') || name.replace(/_/g, ' ');
+    };
+"#;
+
+// Reads an article in one pass over the parser output
+const ARTICLE_SCRIPT: &str = r#"
+    const lead = [];
+    const sections = [];
+    for (const node of content ? content.children : []) {
+        const heading = headingOf(node);
+        if (heading) {
+            const headline = headlineOf(heading);
+            const level = Number(heading.tagName[1]);
+            sections.push({ title: clean(headline), level, anchor: headline.id || heading.id, paragraphs: [] });
+        } else if (node.tagName === 'P') {
+            const text = clean(node);
+            if (text) (sections.length ? sections[sections.length - 1].paragraphs : lead).push(text);
+        }
+    }
+
+    const infobox = [];
+    const box = content && content.querySelector('table.infobox');
+    for (const row of box ? box.querySelectorAll('tr') : []) {
+        const label = row.querySelector(':scope > th');
+        const data = row.querySelector(':scope > td');
+        if (label && data && clean(label)) infobox.push([clean(label), clean(data)]);
+    }
+
+    const links = new Map();
+    for (const link of content ? content.querySelectorAll('a[href]') : []) {
+        if (link.closest('.reflist, .references, .navbox, .mw-editsection')) continue;
+        const title = articleTitle(link);
+        const url = new URL(link.href);
+        url.hash = '';
+        if (title !== null && !links.has(url.href)) links.set(url.href, title);
+    }
+
+    const references = Array.from(document.querySelectorAll('ol.references > li'))
+        .map(item => clean(item.querySelector('.reference-text') || item));
+    const categories = Array.from(document.querySelectorAll('#mw-normal-catlinks ul a')).map(clean);
+    const title = document.querySelector('#firstHeading');
+    return {
+        title: title ? clean(title) : document.title,
+        url: location.href,
+        lead,
+        sections,
+        infobox,
+        categories,
+        links: Array.from(links, ([url, title]) => ({ title, url })),
+        references,
+    };
+"#;
+
+// What kind of page a search landed on, with the meanings listed when it is
+// a disambiguation page
+const LANDING_SCRIPT: &str = r#"
+    if (document.body.classList.contains('mw-special-Search')
+        || document.querySelector('.mw-search-results, .mw-search-nonefound') !== null) {
+        const next = document.querySelector('a.mw-nextlink[href]');
+        return { kind: 'results', next: next ? next.href : null };
+    }
+    const categories = Array.from(document.querySelectorAll('#catlinks a')).map(link => link.textContent);
+    if (document.querySelector('#disambigbox, .dmbox-disambig') === null
+        && !categories.some(name => /disambiguation pages$/i.test(name))) {
+        return { kind: 'article' };
+    }
+
+    const options = [];
+    let section = null;
+    for (const node of content ? content.children : []) {
+        const heading = headingOf(node);
+        if (heading) {
+            section = clean(headlineOf(heading));
+            continue;
+        }
+        if (node.matches('.navbox, .dmbox, #disambigbox, .hatnote, .toc, #toc')) continue;
+        for (const item of node.tagName === 'LI' ? [node] : node.querySelectorAll('li')) {
+            // An entry is named by its first link; nested lists are entries of their own
+            const link = Array.from(item.querySelectorAll('a[href]')).find(link => link.closest('li') === item);
+            const title = link ? articleTitle(link) : null;
+            if (title === null) continue;
+            const own = item.cloneNode(true);
+            own.querySelectorAll('ul, ol').forEach(list => list.remove());
+            options.push({ title, url: link.href, description: clean(own), section });
+        }
+    }
+    return { kind: 'disambiguation', options };
+"#;
+
+// The hits listed on a search results page
+const HITS_SCRIPT: &str = r#"
+    return Array.from(document.querySelectorAll('li.mw-search-result')).flatMap(item => {
+        const link = item.querySelector('.mw-search-result-heading a[href]');
+        if (!link) return [];
+        const snippet = item.querySelector('.searchresult');
+        // Such as "120 KB (12,113 words) - 14:02, 20 June 2024"
+        const data = item.querySelector('.mw-search-result-data');
+        const size = data && data.textContent.match(/^\s*([\d.,]+\s*(?:bytes|[KMG]B))/);
+        const words = data && data.textContent.match(/\(([\d,]+) words?\)/);
+        return [{
+            title: link.getAttribute('title') || clean(link),
+            url: link.href,
+            snippet: snippet ? clean(snippet) : '',
+            size: size ? size[1].replace(/\s+/g, ' ') : null,
+            words: words ? Number(words[1].replace(/,/g, '')) : null,
+        }];
+    });
+"#;
+
+// Wrap a script body so it can use the helpers
+fn in_page(body: &str) -> String {
+    format!("(() => {{{}{}}})()", HELPERS, body)
+}
+
+/// Reads articles from Wikipedia, or from another MediaWiki site using the
+/// Vector skin.
+pub struct Wikipedia<'a> {
+    scraper: &'a Scraper,
+    base_url: Url,
+    result_pages: usize,
+}
+
+impl<'a> Wikipedia<'a> {
+    /// An adapter for the live English Wikipedia.
+    pub fn new(scraper: &'a Scraper) -> Self {
+        Self { scraper, base_url: Url::parse(BASE_URL).expect("valid base URL"), result_pages: 1 }
+    }
+
+    /// An adapter for the wiki at `base_url`, such as another language's
+    /// Wikipedia or a local copy.
+    pub fn with_base_url(scraper: &'a Scraper, base_url: &str) -> Result<Self> {
+        let base_url = Url::parse(base_url)
+            .map_err(|e| ScrapeError::Config(format!("invalid wiki URL `{}`: {}", base_url, e)))?;
+        Ok(Self { scraper, base_url, result_pages: 1 })
+    }
+
+    /// Read up to `result_pages` pages of hits when a search lists results.
+    pub fn result_pages(mut self, result_pages: usize) -> Self {
+        self.result_pages = result_pages;
+        self
+    }
+
+    /// URL of the article called `title`.
+    pub fn article_url(&self, title: &str) -> String {
+        let mut url = self.base_url.clone();
+        url.set_path(&format!("wiki/{}", title.replace(' ', "_")));
+        url.to_string()
+    }
+
+    /// URL of the search for `term`, which the wiki redirects to the matching
+    /// article when there is one.
+    pub fn search_url(&self, term: &str) -> String {
+        let mut url = self.base_url.clone();
+        url.set_path("w/index.php");
+        url.query_pairs_mut().append_pair("search", term).append_pair("title", "Special:Search").append_pair("go", "Go");
+        url.to_string()
+    }
+
+    /// Open and read the article called `title`.
+    pub async fn article(&self, title: &str) -> Result<Article> {
+        let page = self.scraper.open(&self.article_url(title)).await?;
+        let article = self.read_article(&page).await;
+        close_page(page, article).await
+    }
+
+    /// Search for `term` and report where it leads: the matching article,
+    /// a list of results, or a disambiguation page.
+    pub async fn search(&self, term: &str) -> Result<SearchOutcome> {
+        let page = self.scraper.open(&self.search_url(term)).await?;
+        let outcome = self.outcome(&page).await;
+        close_page(page, outcome).await
+    }
+
+    /// Search for `term` and read the article it leads to. When the search
+    /// lists results or meanings instead, the one titled like `term` is
+    /// read, or else the first one.
+    pub async fn search_article(&self, term: &str) -> Result<Article> {
+        let page = self.scraper.open(&self.search_url(term)).await?;
+        let article = self.search_article_on(&page, term).await;
+        close_page(page, article).await
+    }
+
+    // Where the search open in `page` landed
+    pub(crate) async fn outcome(&self, page: &Page) -> Result<SearchOutcome> {
+        match self.scraper.evaluate(page, &in_page(LANDING_SCRIPT)).await? {
+            Landing::Article => Ok(SearchOutcome::Article(self.read_article(page).await?)),
+            Landing::Results { next } => {
+                let mut hits = self.hits(page).await?;
+                if let Some(next) = next
+                    && self.result_pages > 1
+                {
+                    let pagination = Pagination::link("a.mw-nextlink").max_pages(self.result_pages - 1);
+                    let more = self
+                        .scraper
+                        .paginate_with(page, &next, &pagination, |page| async move { self.hits(&page).await })
+                        .await?;
+                    hits.extend(more.items);
+                }
+                Ok(SearchOutcome::Results(hits))
+            }
+            Landing::Disambiguation { options } => Ok(SearchOutcome::Disambiguation(options)),
+        }
+    }
+
+    async fn search_article_on(&self, page: &Page, term: &str) -> Result<Article> {
+        let choices: Vec<(String, String)> = match self.scraper.evaluate(page, &in_page(LANDING_SCRIPT)).await? {
+            Landing::Article => return self.read_article(page).await,
+            Landing::Results { .. } => self.hits(page).await?.into_iter().map(|hit| (hit.title, hit.url)).collect(),
+            Landing::Disambiguation { options } => {
+                options.into_iter().map(|option| (option.title, option.url)).collect()
+            }
+        };
+        let best = choices.iter().find(|(title, _)| title.eq_ignore_ascii_case(term)).or(choices.first());
+        match best {
+            Some((_, url)) => self.scraper.goto(page, url).await?,
+            None => {
+                return Err(ScrapeError::NotFound {
+                    step: "wikipedia search",
+                    url: page_url(page).await,
+                    what: format!("an article matching `{}`", term),
+                });
+            }
+        };
+        self.read_article(page).await
+    }
+
+    // The hits listed on the results page open in `page`
+    async fn hits(&self, page: &Page) -> Result<Vec<Hit>> {
+        self.scraper.evaluate(page, &in_page(HITS_SCRIPT)).await
+    }
+
+    /// Read the article open in `page`.
+    pub async fn read_article(&self, page: &Page) -> Result<Article> {
+        let raw: RawArticle = self.scraper.evaluate(page, &in_page(ARTICLE_SCRIPT)).await?;
+        Ok(Article {
+            title: raw.title,
+            url: raw.url,
+            lead: raw.lead.join("\n\n"),
+            sections: nest(raw.sections),
+            infobox: raw.infobox,
+            categories: raw.categories,
+            links: raw.links,
+            references: raw.references,
+        })
+    }
+}
+
+// Turn headings in page order into a tree: each section holds the following
+// sections of a deeper level
+fn nest(flat: Vec<RawSection>) -> Vec<Section> {
+    let mut roots: Vec<Section> = Vec::new();
+    // Sections still open for subsections, outermost first
+    let mut open: Vec<Section> = Vec::new();
+    for raw in flat {
+        let section = Section {
+            title: raw.title,
+            level: raw.level,
+            anchor: raw.anchor,
+            text: raw.paragraphs.join("\n\n"),
+            subsections: Vec::new(),
+        };
+        while open.last().is_some_and(|last| last.level >= section.level) {
+            close_section(&mut open, &mut roots);
+        }
+        open.push(section);
+    }
+    while !open.is_empty() {
+        close_section(&mut open, &mut roots);
+    }
+    roots
+}
+
+fn close_section(open: &mut Vec<Section>, roots: &mut Vec<Section>) {
+    let section = open.pop().expect("a section is open");
+    match open.last_mut() {
+        Some(parent) => parent.subsections.push(section),
+        None => roots.push(section),
+    }
+}
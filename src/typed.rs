@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+use chromiumoxide::Page;
+use serde_json::{Map, Value};
+
+use crate::scraper::page_url;
+use crate::{Field, Result, Schema, ScrapeError, Scraper};
+
+/// A Rust type read from each element matching a selector.
+///
+/// Usually derived with `#[derive(Scrape)]`:
+///
+/// ```ignore
+/// #[derive(Scrape)]
+/// struct Book {
+///     #[scrape(selector = "h3 a", attr = "title")]
+///     title: String,
+///     #[scrape(selector = ".price_color", parse = "price")]
+///     price: f64,
+/// }
+///
+/// let books: Vec<Book> = scraper.extract_all(&page, ".product_pod").await?;
+/// ```
+pub trait Scrape: Sized {
+    /// How to read each field, relative to the record's root element.
+    fn fields() -> BTreeMap<String, Field>;
+
+    /// Build a value from the fields read for one record.
+    fn from_record(record: Record) -> std::result::Result<Self, FieldError>;
+}
+
+/// The fields read for one record, converted as their [`Field`] describes.
+#[derive(Debug, Clone)]
+pub struct Record(Map<String, Value>);
+
+impl Record {
+    /// Remove the field called `name` and convert it to `T`.
+    pub fn take<T: FromField>(&mut self, name: &str) -> std::result::Result<T, FieldError> {
+        let value = self.0.remove(name).unwrap_or(Value::Null);
+        T::from_field(value).map_err(|message| FieldError { field: name.to_string(), message })
+    }
+}
+
+/// A field of a [`Scrape`] type that could not be built.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// Types a single [`Scrape`] field can hold.
+///
+/// Missing values (no matching element, or a regex that did not match) are
+/// only accepted by `Option` and `Vec` fields.
+pub trait FromField: Sized {
+    fn from_field(value: Value) -> std::result::Result<Self, String>;
+}
+
+impl FromField for String {
+    fn from_field(value: Value) -> std::result::Result<Self, String> {
+        match value {
+            Value::String(text) => Ok(text),
+            Value::Null => Err("no value found".to_string()),
+            other => Ok(other.to_string()),
+        }
+    }
+}
+
+impl FromField for Value {
+    fn from_field(value: Value) -> std::result::Result<Self, String> {
+        Ok(value)
+    }
+}
+
+// Numbers and booleans are parsed from the text or the converted number
+macro_rules! from_str_fields {
+    ($($ty:ty),*) => {$(
+        impl FromField for $ty {
+            fn from_field(value: Value) -> std::result::Result<Self, String> {
+                let text = match value {
+                    Value::String(text) => text,
+                    Value::Null => return Err("no value found".to_string()),
+                    other => other.to_string(),
+                };
+                text.trim()
+                    .parse()
+                    .map_err(|e| format!("`{}` is not a valid {}: {}", text, stringify!($ty), e))
+            }
+        }
+    )*};
+}
+
+from_str_fields!(f32, f64, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, bool);
+
+impl<T: FromField> FromField for Option<T> {
+    fn from_field(value: Value) -> std::result::Result<Self, String> {
+        match value {
+            Value::Null => Ok(None),
+            value => T::from_field(value).map(Some),
+        }
+    }
+}
+
+impl<T: FromField> FromField for Vec<T> {
+    fn from_field(value: Value) -> std::result::Result<Self, String> {
+        match value {
+            Value::Array(values) => values
+                .into_iter()
+                .enumerate()
+                .map(|(index, value)| T::from_field(value).map_err(|message| format!("item {}: {}", index, message)))
+                .collect(),
+            Value::Null => Ok(Vec::new()),
+            value => Ok(vec![T::from_field(value)?]),
+        }
+    }
+}
+
+impl Scraper {
+    /// Read a `T` from every element of `page` matching `selector`.
+    ///
+    /// Fails with [`ScrapeError::Field`] naming the first field that is
+    /// missing or cannot be converted.
+    pub async fn extract_all<T: Scrape>(&self, page: &Page, selector: &str) -> Result<Vec<T>> {
+        let schema = Schema { root: selector.to_string(), fields: T::fields() };
+        let records = self.read_records(page, &schema, "extract all").await?;
+        let mut values = Vec::with_capacity(records.len());
+        for record in records {
+            let record = match record {
+                Value::Object(fields) => Record(fields),
+                _ => Record(Map::new()),
+            };
+            match T::from_record(record) {
+                Ok(value) => values.push(value),
+                Err(e) => {
+                    return Err(ScrapeError::Field {
+                        step: "extract all",
+                        url: page_url(page).await,
+                        field: e.field,
+                        message: e.message,
+                    });
+                }
+            }
+        }
+        Ok(values)
+    }
+}
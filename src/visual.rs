@@ -0,0 +1,243 @@
+use std::path::{Path, PathBuf};
+use chromiumoxide::Page;
+use image::{Rgba, RgbaImage};
+
+use crate::scraper::{close_page, js_string, page_url};
+use crate::{Clip, ImageFormat, Result, ScrapeError, Scraper, ScreenshotOptions, WaitFor};
+
+/// An area of the page to leave out of a comparison, such as a clock or an
+/// ad slot that changes on every load.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Mask {
+    /// Every element matching the selector.
+    Selector(String),
+    /// A rectangle of the document, in CSS pixels.
+    Rect(Clip),
+}
+
+/// How to compare a capture against its baseline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffOptions {
+    /// What to capture. Captures are always compared as PNG.
+    pub screenshot: ScreenshotOptions,
+    pub masks: Vec<Mask>,
+    /// Fail when more than this percentage of compared pixels differ.
+    pub threshold: f64,
+    /// How far a color channel may drift, out of 255, before a pixel counts
+    /// as different. Absorbs anti-aliasing noise.
+    pub tolerance: u8,
+    /// Where to write an image highlighting the differences, if anywhere.
+    pub diff_path: Option<PathBuf>,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            screenshot: ScreenshotOptions::default(),
+            masks: Vec::new(),
+            threshold: 0.1,
+            tolerance: 16,
+            diff_path: None,
+        }
+    }
+}
+
+impl DiffOptions {
+    pub fn screenshot(mut self, screenshot: ScreenshotOptions) -> Self {
+        self.screenshot = screenshot;
+        self
+    }
+
+    /// Ignore every element matching `selector`.
+    pub fn mask(mut self, selector: impl Into<String>) -> Self {
+        self.masks.push(Mask::Selector(selector.into()));
+        self
+    }
+
+    /// Ignore a rectangle of the document.
+    pub fn mask_rect(mut self, rect: Clip) -> Self {
+        self.masks.push(Mask::Rect(rect));
+        self
+    }
+
+    pub fn threshold(mut self, percent: f64) -> Self {
+        self.threshold = percent;
+        self
+    }
+
+    pub fn tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn diff_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.diff_path = Some(path.into());
+        self
+    }
+}
+
+/// The outcome of a comparison within the threshold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VisualDiff {
+    /// Pixels that differ, out of `compared`.
+    pub differing: u64,
+    /// Pixels compared: the larger of the two images, less the masks.
+    pub compared: u64,
+    pub percent: f64,
+    /// Whether the images were of different sizes. Pixels only one of them
+    /// covers count as differing.
+    pub resized: bool,
+    /// Whether there was no baseline yet, so the capture became it.
+    pub new_baseline: bool,
+}
+
+impl Scraper {
+    /// Capture `page` and compare it with the image at `baseline`.
+    ///
+    /// When `baseline` does not exist yet, the capture is saved there as PNG
+    /// and reported as a new baseline. Fails with
+    /// [`ScrapeError::VisualMismatch`] when more than
+    /// [`DiffOptions::threshold`] percent of the pixels differ; the diff
+    /// image is written either way.
+    pub async fn compare_screenshot(&self, page: &Page, baseline: &Path, options: &DiffOptions) -> Result<VisualDiff> {
+        let screenshot = ScreenshotOptions { format: ImageFormat::Png, quality: None, ..options.screenshot.clone() };
+        // Where the capture sits in the document, to place the masks on it
+        let area = self.region_rect(page, &screenshot.region).await?;
+        let mut masks = Vec::new();
+        for mask in &options.masks {
+            match mask {
+                Mask::Selector(selector) => {
+                    let script = format!(
+                        "Array.from(document.querySelectorAll({})).map(element => {{ \
+                         const rect = element.getBoundingClientRect(); \
+                         return [rect.left + scrollX, rect.top + scrollY, rect.width, rect.height]; }})",
+                        js_string(selector)
+                    );
+                    let rects: Vec<[f64; 4]> = self.evaluate(page, &script).await?;
+                    masks.extend(rects.into_iter().map(|[x, y, width, height]| Clip { x, y, width, height }));
+                }
+                Mask::Rect(rect) => masks.push(*rect),
+            }
+        }
+        let capture = self.screenshot(page, &screenshot).await?;
+        let current = decode("capture", &capture)?;
+
+        if !baseline.exists() {
+            std::fs::write(baseline, &capture).map_err(|e| ScrapeError::io("save baseline", baseline, e))?;
+            let compared = u64::from(current.width()) * u64::from(current.height());
+            return Ok(VisualDiff { differing: 0, compared, percent: 0., resized: false, new_baseline: true });
+        }
+        let stored = std::fs::read(baseline).map_err(|e| ScrapeError::io("read baseline", baseline, e))?;
+        let expected = decode(&baseline.display().to_string(), &stored)?;
+
+        // Image pixels per CSS pixel of the capture
+        let ratio = f64::from(current.width()) / area.width.max(1.);
+        let masks: Vec<PixelRect> = masks.iter().map(|mask| PixelRect::place(mask, &area, ratio)).collect();
+        let (diff, image) = compare(&expected, &current, &masks, options.tolerance);
+
+        if let Some(path) = &options.diff_path {
+            image.save(path).map_err(|e| ScrapeError::Image {
+                step: "compare screenshot",
+                what: path.display().to_string(),
+                message: e.to_string(),
+            })?;
+        }
+        if diff.percent > options.threshold {
+            return Err(ScrapeError::VisualMismatch {
+                step: "compare screenshot",
+                url: page_url(page).await,
+                baseline: baseline.to_path_buf(),
+                percent: diff.percent,
+                threshold: options.threshold,
+                diff: options.diff_path.clone(),
+            });
+        }
+        Ok(diff)
+    }
+
+    /// Open `url`, wait for `ready` to hold, and compare a capture with the
+    /// image at `baseline` as with [`Scraper::compare_screenshot`].
+    pub async fn take_screenshot_compared(
+        &self,
+        url: &str,
+        baseline: &Path,
+        ready: &WaitFor,
+        options: &DiffOptions,
+    ) -> Result<VisualDiff> {
+        let page = self.open(url).await?;
+        let result = match self.wait(&page, ready).await {
+            Ok(()) => self.compare_screenshot(&page, baseline, options).await,
+            Err(e) => Err(e),
+        };
+        close_page(page, result).await
+    }
+}
+
+fn decode(what: &str, bytes: &[u8]) -> Result<RgbaImage> {
+    match image::load_from_memory(bytes) {
+        Ok(image) => Ok(image.to_rgba8()),
+        Err(e) => Err(ScrapeError::Image { step: "compare screenshot", what: what.to_string(), message: e.to_string() }),
+    }
+}
+
+// A mask in image pixels, as half-open ranges
+struct PixelRect {
+    x: std::ops::Range<u32>,
+    y: std::ops::Range<u32>,
+}
+
+impl PixelRect {
+    // Place a document rectangle on a capture of `area`
+    fn place(rect: &Clip, area: &Clip, ratio: f64) -> Self {
+        let to_pixel = |offset: f64| (offset * ratio).round().max(0.) as u32;
+        let (left, top) = (to_pixel(rect.x - area.x), to_pixel(rect.y - area.y));
+        let (right, bottom) = (to_pixel(rect.x + rect.width - area.x), to_pixel(rect.y + rect.height - area.y));
+        Self { x: left..right, y: top..bottom }
+    }
+
+    fn contains(&self, x: u32, y: u32) -> bool {
+        self.x.contains(&x) && self.y.contains(&y)
+    }
+}
+
+const CHANGED: Rgba<u8> = Rgba([230, 30, 30, 255]);
+const MASKED: Rgba<u8> = Rgba([120, 150, 230, 255]);
+
+// Count the pixels that differ and draw them in red over a faded copy of
+// the capture; masked areas are drawn in blue
+fn compare(expected: &RgbaImage, current: &RgbaImage, masks: &[PixelRect], tolerance: u8) -> (VisualDiff, RgbaImage) {
+    let width = expected.width().max(current.width());
+    let height = expected.height().max(current.height());
+    let mut image = RgbaImage::new(width, height);
+    let (mut differing, mut compared) = (0, 0);
+    for (x, y, out) in image.enumerate_pixels_mut() {
+        if masks.iter().any(|mask| mask.contains(x, y)) {
+            *out = MASKED;
+            continue;
+        }
+        compared += 1;
+        let pixels = (expected.get_pixel_checked(x, y), current.get_pixel_checked(x, y));
+        *out = match pixels {
+            (Some(before), Some(after)) if !differs(before, after, tolerance) => faded(after),
+            _ => {
+                differing += 1;
+                CHANGED
+            }
+        };
+    }
+    let percent = if compared == 0 { 0. } else { differing as f64 * 100. / compared as f64 };
+    let resized = expected.dimensions() != current.dimensions();
+    (VisualDiff { differing, compared, percent, resized, new_baseline: false }, image)
+}
+
+fn differs(before: &Rgba<u8>, after: &Rgba<u8>, tolerance: u8) -> bool {
+    before.0.iter().zip(after.0).any(|(a, b)| a.abs_diff(b) > tolerance)
+}
+
+// A light gray version of a pixel, so changes stand out
+fn faded(pixel: &Rgba<u8>) -> Rgba<u8> {
+    let [r, g, b, _] = pixel.0.map(u32::from);
+    let luma = (r * 299 + g * 587 + b * 114) / 1000;
+    let light = (255 - (255 - luma) / 4) as u8;
+    Rgba([light, light, light, 255])
+}
@@ -0,0 +1,226 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use chromiumoxide::Page;
+
+use crate::network::NetworkTracker;
+use crate::scraper::{js_string, page_url};
+use crate::{Result, ScrapeError, Scraper};
+
+/// How long to wait for a condition and how often to check it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WaitOptions {
+    pub timeout: Duration,
+    pub interval: Duration,
+}
+
+impl Default for WaitOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            interval: Duration::from_millis(100),
+        }
+    }
+}
+
+impl WaitOptions {
+    /// Wait up to `timeout`, checking at the default interval.
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout, ..Self::default() }
+    }
+
+    /// Check the condition every `interval`.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+}
+
+/// A condition to wait for on a page.
+///
+/// Patterns are JavaScript regular expressions, evaluated in the page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WaitFor {
+    /// An element matching the selector exists.
+    Selector(String),
+    /// An element matching the selector exists, is displayed and has a size.
+    Visible(String),
+    /// No element matches the selector.
+    Gone(String),
+    /// The text content of the first element matching `selector` matches `pattern`.
+    Text { selector: String, pattern: String },
+    /// The page URL matches the pattern.
+    Url(String),
+    /// No network request has been in flight for the given duration.
+    ///
+    /// Requests are counted from when a tab the helpers opened was created.
+    /// On other tabs only requests started after the wait began are seen.
+    NetworkIdle(Duration),
+    /// A JavaScript expression evaluates to a truthy value.
+    Predicate(String),
+}
+
+impl WaitFor {
+    /// Wait for an element matching `selector` to exist.
+    pub fn selector(selector: impl Into<String>) -> Self {
+        WaitFor::Selector(selector.into())
+    }
+
+    /// Wait for an element matching `selector` to be displayed.
+    pub fn visible(selector: impl Into<String>) -> Self {
+        WaitFor::Visible(selector.into())
+    }
+
+    /// Wait for no element to match `selector`.
+    pub fn gone(selector: impl Into<String>) -> Self {
+        WaitFor::Gone(selector.into())
+    }
+
+    /// Wait for the text of the first element matching `selector` to match `pattern`.
+    pub fn text(selector: impl Into<String>, pattern: impl Into<String>) -> Self {
+        WaitFor::Text { selector: selector.into(), pattern: pattern.into() }
+    }
+
+    /// Wait for the page URL to match `pattern`.
+    pub fn url(pattern: impl Into<String>) -> Self {
+        WaitFor::Url(pattern.into())
+    }
+
+    /// Wait for no request to be in flight for `idle`.
+    pub fn network_idle(idle: Duration) -> Self {
+        WaitFor::NetworkIdle(idle)
+    }
+
+    /// Wait for the JavaScript `expression` to be truthy.
+    pub fn predicate(expression: impl Into<String>) -> Self {
+        WaitFor::Predicate(expression.into())
+    }
+
+    // JavaScript expression that evaluates to true once the condition holds
+    fn script(&self) -> Option<String> {
+        let script = match self {
+            WaitFor::Selector(selector) => format!("document.querySelector({}) !== null", js_string(selector)),
+            WaitFor::Visible(selector) => format!(
+                r#"(() => {{
+                    const element = document.querySelector({});
+                    if (!element) return false;
+                    const style = getComputedStyle(element);
+                    const rect = element.getBoundingClientRect();
+                    return style.display !== 'none' && style.visibility !== 'hidden'
+                        && rect.width > 0 && rect.height > 0;
+                }})()"#,
+                js_string(selector)
+            ),
+            WaitFor::Gone(selector) => format!("document.querySelector({}) === null", js_string(selector)),
+            WaitFor::Text { selector, pattern } => format!(
+                "(() => {{ const element = document.querySelector({}); \
+                 return !!element && new RegExp({}).test(element.textContent); }})()",
+                js_string(selector),
+                js_string(pattern)
+            ),
+            WaitFor::Url(pattern) => format!("new RegExp({}).test(location.href)", js_string(pattern)),
+            WaitFor::Predicate(expression) => format!("!!({})", expression),
+            WaitFor::NetworkIdle(_) => return None,
+        };
+        Some(script)
+    }
+}
+
+impl fmt::Display for WaitFor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaitFor::Selector(selector) => write!(f, "`{}` to exist", selector),
+            WaitFor::Visible(selector) => write!(f, "`{}` to be visible", selector),
+            WaitFor::Gone(selector) => write!(f, "`{}` to disappear", selector),
+            WaitFor::Text { selector, pattern } => write!(f, "text of `{}` to match /{}/", selector, pattern),
+            WaitFor::Url(pattern) => write!(f, "URL to match /{}/", pattern),
+            WaitFor::NetworkIdle(idle) => write!(f, "network to be idle for {:?}", idle),
+            WaitFor::Predicate(expression) => write!(f, "`{}` to be truthy", expression),
+        }
+    }
+}
+
+impl Scraper {
+    /// Default wait options for this session, from its [`Timeouts`](crate::Timeouts).
+    pub fn wait_options(&self) -> WaitOptions {
+        WaitOptions::new(self.timeouts().wait())
+    }
+
+    /// Wait for `condition` using the session's default wait options.
+    pub async fn wait(&self, page: &Page, condition: &WaitFor) -> Result<()> {
+        self.wait_with(page, condition, self.wait_options()).await
+    }
+
+    /// Wait for `condition`, checking every `options.interval` until
+    /// `options.timeout` elapses.
+    pub async fn wait_with(&self, page: &Page, condition: &WaitFor, options: WaitOptions) -> Result<()> {
+        let deadline = Instant::now() + options.timeout;
+        match condition {
+            WaitFor::NetworkIdle(idle) => {
+                let tracker = match self.network_tracker(page) {
+                    Some(tracker) => tracker,
+                    None => Arc::new(NetworkTracker::attach(page, 0).await?),
+                };
+                poll_until(page, condition, options, deadline, || async {
+                    Ok(tracker.quiet_for().is_some_and(|quiet| quiet >= *idle))
+                })
+                .await
+            }
+            _ => {
+                let script = condition.script().unwrap_or_default();
+                poll_until(page, condition, options, deadline, || async {
+                    match self.evaluate::<bool>(page, &script).await {
+                        Ok(done) => Ok(done),
+                        // The page may be between documents; keep polling
+                        Err(ScrapeError::Cdp { .. }) | Err(ScrapeError::Deserialize { .. }) => Ok(false),
+                        Err(e) => Err(e),
+                    }
+                })
+                .await
+            }
+        }
+    }
+
+    // Count the requests of `page` from now on, for `WaitFor::NetworkIdle`
+    pub(crate) async fn track_network(&self, page: &Page) -> Result<()> {
+        let tracker = NetworkTracker::attach(page, 0).await?;
+        let mut trackers = self.network_trackers.lock().unwrap();
+        trackers.retain(|(_, tracker)| !tracker.is_closed());
+        trackers.push((page.target_id().clone(), Arc::new(tracker)));
+        Ok(())
+    }
+
+    fn network_tracker(&self, page: &Page) -> Option<Arc<NetworkTracker>> {
+        let trackers = self.network_trackers.lock().unwrap();
+        trackers.iter().find(|(target, _)| target == page.target_id()).map(|(_, tracker)| tracker.clone())
+    }
+}
+
+// Call `check` every `options.interval` until it returns true or `deadline` passes
+async fn poll_until<F, Fut>(
+    page: &Page,
+    condition: &WaitFor,
+    options: WaitOptions,
+    deadline: Instant,
+    mut check: F,
+) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<bool>>,
+{
+    loop {
+        if check().await? {
+            return Ok(());
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(ScrapeError::WaitTimeout {
+                step: "wait",
+                url: page_url(page).await,
+                condition: condition.to_string(),
+                timeout: options.timeout,
+            });
+        }
+        async_std::task::sleep(options.interval.min(deadline - now)).await;
+    }
+}
@@ -0,0 +1,139 @@
+//! Regression suite for the books.toscrape.com adapter, run against saved
+//! copies of the site's pages in `tests/fixtures/books`.
+
+mod common;
+
+use common::{launch, FixtureServer};
+use rust_scraper::sites::books::{Book, BooksToScrape, Category};
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn lists_categories_from_the_sidebar() {
+    let site = FixtureServer::start("books");
+    let scraper = launch().await;
+
+    let categories = BooksToScrape::with_base_url(&scraper, site.url("")).categories().await.unwrap();
+
+    assert_eq!(
+        categories,
+        [
+            Category { name: "Travel".into(), url: site.url("catalogue/category/books/travel_2/index.html") },
+            Category { name: "Poetry".into(), url: site.url("catalogue/category/books/poetry_23/index.html") },
+        ]
+    );
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn follows_listing_pages() {
+    let site = FixtureServer::start("books");
+    let scraper = launch().await;
+    let books = BooksToScrape::with_base_url(&scraper, site.url(""));
+    let poetry = Category { name: "Poetry".into(), url: site.url("catalogue/category/books/poetry_23/index.html") };
+
+    let page = scraper.browser().new_page("about:blank").await.unwrap();
+    let urls = books.book_urls(&page, &poetry).await.unwrap();
+
+    assert_eq!(
+        urls,
+        [
+            site.url("catalogue/a-light-in-the-attic_1000/index.html"),
+            site.url("catalogue/olio_984/index.html"),
+            site.url("catalogue/shakespeares-sonnets_989/index.html"),
+        ]
+    );
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn reads_every_detail_field() {
+    let site = FixtureServer::start("books");
+    let scraper = launch().await;
+    let books = BooksToScrape::with_base_url(&scraper, site.url(""));
+
+    let page = scraper.browser().new_page("about:blank").await.unwrap();
+    let url = site.url("catalogue/olio_984/index.html");
+    let book = books.book(&page, &url).await.unwrap();
+
+    assert_eq!(
+        book,
+        Book {
+            title: "Olio".into(),
+            price: 23.88,
+            price_incl_tax: 28.66,
+            tax: 4.78,
+            availability: 19,
+            rating: 1,
+            upc: "9528d0948525bf5f".into(),
+            description: Some(
+                "Part fact, part fiction, Tyehimba Jess's much anticipated second book weaves sonnet, song, and \
+                 narrative to examine the lives of mostly unrecorded African American performers. ...more"
+                    .into()
+            ),
+            category: "Poetry".into(),
+            image_url: site.url("media/cache/b1/0e/b10eabab1e1c811a6d47969904fd5755.jpg"),
+            url,
+        }
+    );
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn description_is_optional() {
+    let site = FixtureServer::start("books");
+    let scraper = launch().await;
+    let books = BooksToScrape::with_base_url(&scraper, site.url(""));
+
+    let page = scraper.browser().new_page("about:blank").await.unwrap();
+    let book = books.book(&page, &site.url("catalogue/shakespeares-sonnets_989/index.html")).await.unwrap();
+
+    assert_eq!(book.title, "Shakespeare's Sonnets");
+    assert_eq!(book.description, None);
+    assert_eq!((book.availability, book.rating), (1, 4));
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn walks_the_whole_catalogue() {
+    let site = FixtureServer::start("books");
+    let scraper = launch().await;
+
+    let books = BooksToScrape::with_base_url(&scraper, site.url("")).concurrency(2).catalogue().await.unwrap();
+    let books: Vec<Book> = books.into_iter().map(Result::unwrap).collect();
+
+    let summary: Vec<(&str, &str, u8)> =
+        books.iter().map(|book| (book.upc.as_str(), book.category.as_str(), book.rating)).collect();
+    assert_eq!(
+        summary,
+        [
+            ("a22124811bfa8350", "Travel", 2),
+            ("ce60436f52c5ee68", "Travel", 4),
+            ("a897fe39b1053632", "Poetry", 3),
+            ("9528d0948525bf5f", "Poetry", 1),
+            ("30a7f60cd76ca58c", "Poetry", 4),
+        ]
+    );
+    assert_eq!(books.iter().map(|book| book.availability).sum::<u32>(), 76);
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn reports_detail_pages_that_fail_in_place() {
+    let site = FixtureServer::start("books");
+    let scraper = launch().await;
+    let withdrawn =
+        Category { name: "Withdrawn".into(), url: site.url("catalogue/category/books/withdrawn_99/index.html") };
+
+    let books = BooksToScrape::with_base_url(&scraper, site.url("")).books_in(&[withdrawn]).await.unwrap();
+
+    assert_eq!(books.len(), 2);
+    assert_eq!(books[0].as_ref().unwrap().upc, "9528d0948525bf5f");
+    let error = books[1].as_ref().unwrap_err();
+    assert_eq!(error.url(), Some(site.url("catalogue/taken-down_999/index.html").as_str()));
+    scraper.close().await.unwrap();
+}
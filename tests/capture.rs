@@ -0,0 +1,78 @@
+//! Response capture, against the page in `tests/fixtures/capture`, which
+//! renders its product list from two JSON API calls.
+
+mod common;
+
+use std::time::Duration;
+
+use common::{launch, FixtureServer};
+use futures::StreamExt;
+use regex::Regex;
+use rust_scraper::{CapturedResponse, ResourceType, UrlPattern};
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Product {
+    id: u32,
+    name: String,
+    price: String,
+}
+
+#[test]
+fn reads_headers_and_parses_bodies() {
+    let body = br#"[{ "id": 1, "name": "Kettle", "price": "24.99" }]"#.to_vec();
+    let response = CapturedResponse {
+        url: "https://shop.example/api/products".to_string(),
+        status: 200,
+        headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+        mime_type: "application/json".to_string(),
+        resource_type: ResourceType::Xhr,
+        json: serde_json::from_slice(&body).ok(),
+        body,
+    };
+    assert_eq!(response.header("content-type"), Some("application/json"));
+    assert_eq!(response.header("etag"), None);
+    let products: Vec<Product> = response.parse().unwrap();
+    assert_eq!(products, [Product { id: 1, name: "Kettle".into(), price: "24.99".into() }]);
+    assert_eq!(response.json.as_ref().unwrap()[0]["name"], "Kettle");
+    assert!(response.parse::<Product>().is_err());
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn collects_matching_json_responses() {
+    let site = FixtureServer::start("capture");
+    let scraper = launch().await;
+
+    let patterns = [UrlPattern::glob("*/api/*")];
+    let quiet = Duration::from_millis(500);
+    let responses = scraper.open_capturing(&site.url(""), &patterns, quiet).await.unwrap();
+
+    let urls: Vec<&str> = responses.iter().map(|response| response.url.as_str()).collect();
+    assert_eq!(urls, [site.url("api/products.json"), site.url("api/stock.json?ids=1,2")]);
+    let products: Vec<Product> = responses[0].parse().unwrap();
+    assert_eq!(products[1], Product { id: 2, name: "Teapot".into(), price: "18.50".into() });
+    assert_eq!(responses[0].status, 200);
+    assert_eq!(responses[0].resource_type, ResourceType::Fetch);
+    assert_eq!(responses[0].header("content-type"), Some("application/json"));
+    assert_eq!(responses[1].json, Some(json!({ "1": 12, "2": 0 })));
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn streams_responses_as_they_arrive() {
+    let site = FixtureServer::start("capture");
+    let scraper = launch().await;
+    let page = scraper.browser().new_page("about:blank").await.unwrap();
+
+    let patterns = [UrlPattern::regex(Regex::new(r"stock\.json").unwrap())];
+    let mut capture = scraper.capture_responses(&page, &patterns).await.unwrap();
+    scraper.goto(&page, &site.url("")).await.unwrap();
+
+    let stock = capture.next().await.unwrap();
+    assert_eq!(stock.text().trim(), r#"{ "1": 12, "2": 0 }"#);
+    assert!(capture.drain().is_empty());
+    scraper.close().await.unwrap();
+}
@@ -0,0 +1,208 @@
+//! Helpers shared by the integration tests: a static file server for saved
+//! fixture pages and a browser launched from the usual config.
+//!
+//! Tests that drive a browser are `#[ignore]`d because they need Chrome;
+//! run them with `cargo test -- --ignored`, setting `SCRAPER_CHROME` and
+//! `SCRAPER_SANDBOX=false` as needed.
+
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rust_scraper::{Scraper, ScraperConfig};
+
+/// Serves the files under a fixture directory over HTTP on localhost until
+/// the test process exits.
+///
+/// Paths map to files; a path ending in `/` serves its `index.html`. Files
+/// without an extension are served as HTML, like saved wiki pages. Requests
+/// with a query string, which files cannot stand for, are answered through
+/// [`FixtureServer::route`] and [`FixtureServer::redirect`]; slow responses
+/// are set up with [`FixtureServer::delay`].
+pub struct FixtureServer {
+    port: u16,
+    routes: Routes,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+/// A request the server received.
+#[derive(Debug, Clone)]
+pub struct Request {
+    /// Path and query, e.g. `/style.css`.
+    pub target: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl Request {
+    /// The value of the header called `name`, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, value)| value.as_str())
+    }
+}
+
+// Responses for exact request targets, such as `/w/index.php?search=rust`
+type Routes = Arc<Mutex<HashMap<String, Route>>>;
+
+#[derive(Clone)]
+enum Route {
+    File(String),
+    Redirect(String),
+    Delay(Duration),
+}
+
+impl FixtureServer {
+    /// Serve `tests/fixtures/<name>`.
+    pub fn start(name: &str) -> Self {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name);
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind fixture server");
+        let port = listener.local_addr().unwrap().port();
+        let routes = Routes::default();
+        let requests = Arc::default();
+        let (shared, log) = (Arc::clone(&routes), Arc::clone(&requests));
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let (root, routes, log) = (root.clone(), Arc::clone(&shared), Arc::clone(&log));
+                std::thread::spawn(move || serve(stream, &root, &routes, &log));
+            }
+        });
+        Self { port, routes, requests }
+    }
+
+    /// Every request received so far, in order.
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Whether a request for `target` has been received.
+    pub fn was_requested(&self, target: &str) -> bool {
+        self.requests.lock().unwrap().iter().any(|request| request.target == target)
+    }
+
+    /// Answer requests for `target` (path and query) with the fixture `file`.
+    pub fn route(&self, target: &str, file: &str) {
+        self.routes.lock().unwrap().insert(target.to_string(), Route::File(file.to_string()));
+    }
+
+    /// Answer requests for `target` with a 302 redirect to `location`.
+    pub fn redirect(&self, target: &str, location: &str) {
+        self.routes.lock().unwrap().insert(target.to_string(), Route::Redirect(location.to_string()));
+    }
+
+    /// Answer requests for `target` as usual, but only after `delay`.
+    pub fn delay(&self, target: &str, delay: Duration) {
+        self.routes.lock().unwrap().insert(target.to_string(), Route::Delay(delay));
+    }
+
+    /// Absolute URL of `path` on this server.
+    pub fn url(&self, path: &str) -> String {
+        format!("http://127.0.0.1:{}/{}", self.port, path.trim_start_matches('/'))
+    }
+}
+
+// Answer one request with the file it names, or 404
+fn serve(mut stream: TcpStream, root: &Path, routes: &Routes, log: &Mutex<Vec<Request>>) {
+    let mut request_line = String::new();
+    let mut reader = BufReader::new(&stream);
+    if reader.read_line(&mut request_line).is_err() {
+        return;
+    }
+    // Read the headers so the browser sees a clean response
+    let mut headers = Vec::new();
+    let mut header = String::new();
+    while reader.read_line(&mut header).is_ok_and(|read| read > 2) {
+        if let Some((name, value)) = header.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+        header.clear();
+    }
+
+    let target = request_line.split_whitespace().nth(1).unwrap_or("/");
+    log.lock().unwrap().push(Request { target: target.to_string(), headers });
+    let route = routes.lock().unwrap().get(target).cloned();
+    let file_path = || percent_decode(target.split(['?', '#']).next().unwrap_or("/"));
+    let path = match route {
+        Some(Route::Redirect(location)) => {
+            let response = format!(
+                "HTTP/1.1 302 Found\r\nLocation: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                location
+            );
+            let _ = stream.write_all(response.as_bytes());
+            return;
+        }
+        Some(Route::File(file)) => file,
+        Some(Route::Delay(delay)) => {
+            std::thread::sleep(delay);
+            file_path()
+        }
+        None => file_path(),
+    };
+    let mut file = root.join(path.trim_start_matches('/'));
+    if path.ends_with('/') {
+        file.push("index.html");
+    }
+    let response = match resolve(root, file).and_then(|file| std::fs::read(&file).ok().map(|body| (file, body))) {
+        Some((file, body)) => {
+            let mut head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                content_type(&file),
+                body.len()
+            )
+            .into_bytes();
+            head.extend(body);
+            head
+        }
+        None => b"HTTP/1.1 404 Not Found\r\nContent-Type: text/html\r\nContent-Length: 9\r\nConnection: close\r\n\r\nNot Found"
+            .to_vec(),
+    };
+    let _ = stream.write_all(&response);
+}
+
+// `file` if it exists and does not escape `root`
+fn resolve(root: &Path, file: PathBuf) -> Option<PathBuf> {
+    let file = file.canonicalize().ok()?;
+    (file.starts_with(root.canonicalize().ok()?) && file.is_file()).then_some(file)
+}
+
+fn content_type(file: &Path) -> &'static str {
+    match file.extension().and_then(|extension| extension.to_str()) {
+        Some("html") | None => "text/html; charset=utf-8",
+        Some("css") => "text/css",
+        Some("js") => "application/javascript",
+        Some("json") => "application/json",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        _ => "application/octet-stream",
+    }
+}
+
+// Decode `%XX` escapes in a request path
+fn percent_decode(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%').then(|| path.get(i + 1..i + 3)).flatten();
+        match escaped.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Launch a browser configured from `SCRAPER_CONFIG` and `SCRAPER_*`.
+pub async fn launch() -> Scraper {
+    let config = ScraperConfig::load(None).expect("load scraper config");
+    Scraper::from_config(&config).await.expect("launch browser")
+}
@@ -0,0 +1,79 @@
+//! Config loading: environment overrides and window sizes. None of this
+//! launches a browser.
+
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use rust_scraper::{parse_window_size, BrowserMode, ScrapeError, ScraperConfig};
+
+fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+}
+
+#[test]
+fn parses_window_sizes() {
+    assert_eq!(parse_window_size("1280x800"), Some((1280, 800)));
+    assert_eq!(parse_window_size("390X844"), Some((390, 844)));
+    assert_eq!(parse_window_size(" 800 x 600 "), Some((800, 600)));
+    assert_eq!(parse_window_size("1280"), None);
+    assert_eq!(parse_window_size("1280x"), None);
+    assert_eq!(parse_window_size("-1x800"), None);
+}
+
+#[test]
+fn overrides_settings_from_the_environment() {
+    let mut config = ScraperConfig::default();
+    config
+        .apply_env(vars(&[
+            ("SCRAPER_MODE", "new-headless"),
+            ("SCRAPER_WINDOW_SIZE", "390x844"),
+            ("SCRAPER_CHROME", "/usr/bin/chromium"),
+            ("SCRAPER_ARGS", "--lang=fr  --mute-audio"),
+            ("SCRAPER_PROXY", "socks5://127.0.0.1:1080"),
+            ("SCRAPER_SANDBOX", "false"),
+            ("SCRAPER_NAVIGATION_TIMEOUT_MS", "45000"),
+            ("SCRAPER_WAIT_TIMEOUT_MS", " 2500 "),
+            ("HOME", "/root"),
+        ]))
+        .unwrap();
+
+    let defaults = ScraperConfig::default();
+    assert_eq!(config.browser.mode, BrowserMode::NewHeadless);
+    assert_eq!(config.browser.window_size, (390, 844));
+    assert_eq!(config.browser.executable, Some(PathBuf::from("/usr/bin/chromium")));
+    assert_eq!(config.browser.args[defaults.browser.args.len()..], ["--lang=fr", "--mute-audio"]);
+    assert_eq!(config.browser.proxy.as_deref(), Some("socks5://127.0.0.1:1080"));
+    assert!(!config.browser.sandbox);
+    assert_eq!(config.timeouts.navigation_ms, 45_000);
+    assert_eq!(config.timeouts.wait_ms, 2_500);
+    assert_eq!(config.timeouts.launch_ms, defaults.timeouts.launch_ms);
+}
+
+#[test]
+fn rejects_invalid_values() {
+    for (key, value) in [
+        ("SCRAPER_WINDOW_SIZE", "wide"),
+        ("SCRAPER_WINDOW_SIZE", "1280x"),
+        ("SCRAPER_MODE", "invisible"),
+        ("SCRAPER_SANDBOX", "maybe"),
+        ("SCRAPER_REQUEST_TIMEOUT_MS", "30s"),
+        ("SCRAPER_LAUNCH_TIMEOUT_MS", "-1"),
+    ] {
+        let error = ScraperConfig::default().apply_env(vars(&[(key, value)])).unwrap_err();
+        assert!(matches!(&error, ScrapeError::Config(message) if message.contains(key)), "{}: {}", key, error);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn ignores_other_variables_that_are_not_utf8() {
+    use std::os::unix::ffi::OsStringExt;
+
+    let junk = OsString::from_vec(vec![0xff, 0xfe]);
+    let mut config = ScraperConfig::default();
+    config.apply_env([(OsString::from("JUNK"), junk.clone()), (junk.clone(), OsString::from("1"))]).unwrap();
+    assert_eq!(config, ScraperConfig::default());
+
+    let error = config.apply_env([(OsString::from("SCRAPER_PROXY"), junk)]).unwrap_err();
+    assert!(matches!(&error, ScrapeError::Config(message) if message.contains("SCRAPER_PROXY")), "{}", error);
+}
@@ -0,0 +1,98 @@
+//! Console collection, against the page in `tests/fixtures/console`, which
+//! logs at every level, requests a missing image and then throws.
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use common::{launch, FixtureServer};
+use rust_scraper::{ConsoleLevel, ConsoleMessage, ConsoleOptions, ConsoleSource};
+
+#[test]
+fn parses_orders_and_prints_levels() {
+    assert_eq!("warn".parse::<ConsoleLevel>(), Ok(ConsoleLevel::Warning));
+    assert_eq!("verbose".parse::<ConsoleLevel>(), Ok(ConsoleLevel::Debug));
+    assert!("fatal".parse::<ConsoleLevel>().is_err());
+    assert!(ConsoleLevel::Debug < ConsoleLevel::Info && ConsoleLevel::Warning < ConsoleLevel::Error);
+
+    let message = ConsoleMessage {
+        level: ConsoleLevel::Error,
+        source: ConsoleSource::Exception,
+        text: "Uncaught TypeError: products is undefined".to_string(),
+        url: Some("https://shop.example/app.js".to_string()),
+        line: Some(12),
+    };
+    let printed = "[error] Uncaught TypeError: products is undefined (https://shop.example/app.js:12)";
+    assert_eq!(message.to_string(), printed);
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn collects_warnings_errors_and_exceptions() {
+    let site = FixtureServer::start("console");
+    let scraper = launch().await;
+    let page = scraper.browser().new_page("about:blank").await.unwrap();
+    let options = ConsoleOptions::default().level(ConsoleLevel::Warning);
+    let collector = scraper.collect_console(&page, &options).await.unwrap();
+    scraper.goto(&page, &site.url("")).await.unwrap();
+
+    let messages = collector.messages();
+    assert!(collector.has_errors());
+    assert!(messages.iter().all(|message| message.level >= ConsoleLevel::Warning));
+    let texts: Vec<&str> = messages.iter().map(|message| message.text.as_str()).collect();
+    assert!(texts.contains(&"slow API") && texts.contains(&"render failed"));
+    let exception = messages.iter().find(|message| message.source == ConsoleSource::Exception).unwrap();
+    assert!(exception.text.contains("TypeError"), "{}", exception.text);
+    assert_eq!(exception.url.as_deref(), Some(site.url("").as_str()));
+    assert_eq!(exception.line, Some(18));
+    let missing = messages.iter().find(|message| message.source == ConsoleSource::Browser("network".into())).unwrap();
+    assert_eq!(missing.url, Some(site.url("missing.png")));
+
+    assert_eq!(collector.take().len(), messages.len());
+    assert!(collector.messages().is_empty());
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn returns_session_messages_with_results() {
+    let site = FixtureServer::start("console");
+    let mut scraper = launch().await;
+    let called = Arc::new(Mutex::new(Vec::new()));
+    let seen = called.clone();
+    let options = ConsoleOptions::default().level(ConsoleLevel::Info).on_message(move |message| {
+        seen.lock().unwrap().push(message.text.clone());
+    });
+    scraper.set_console(Some(options));
+
+    let (products, messages) = scraper
+        .with_console(async {
+            let page = scraper.open(&site.url("")).await?;
+            scraper.extract_from(&page, ".products li", None).await
+        })
+        .await;
+    assert!(products.unwrap().is_empty());
+    let logged = messages.iter().find(|message| message.level == ConsoleLevel::Info).unwrap();
+    // Objects are not serialized, only described
+    assert_eq!(logged.text, "products: 0 Object");
+    assert!(!messages.iter().any(|message| message.text == "rendering"));
+    assert!(messages.iter().any(|message| message.source == ConsoleSource::Exception));
+    assert_eq!(*called.lock().unwrap(), messages.iter().map(|message| message.text.clone()).collect::<Vec<_>>());
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn keeps_messages_of_tabs_closed_before_returning() {
+    let site = FixtureServer::start("console");
+    let mut scraper = launch().await;
+    scraper.set_console(Some(ConsoleOptions::default().level(ConsoleLevel::Error)));
+
+    // `extract` closes its tab as soon as it has read the page
+    let (products, messages) = scraper.with_console(scraper.extract(&site.url(""), ".products li", None)).await;
+    assert!(products.unwrap().is_empty());
+    let texts: Vec<&str> = messages.iter().map(|message| message.text.as_str()).collect();
+    assert!(texts.contains(&"render failed"), "{:?}", texts);
+    assert!(messages.iter().any(|message| message.source == ConsoleSource::Exception), "{:?}", texts);
+    scraper.close().await.unwrap();
+}
@@ -0,0 +1,84 @@
+//! `#[derive(Scrape)]`: attributes rejected at compile time (the cases in
+//! `tests/ui`) and records read from the saved poetry listing in
+//! `tests/fixtures/books`.
+
+mod common;
+
+use common::{launch, FixtureServer};
+use rust_scraper::{FieldType, Scrape, ScrapeError};
+
+#[derive(Debug, PartialEq, Scrape)]
+struct Listing {
+    #[scrape(selector = "h3 a", attr = "title")]
+    title: String,
+    #[scrape(selector = ".price_color", parse = "price")]
+    price: f64,
+    #[scrape(selector = "h3 a", attr = "href", parse = "url")]
+    url: String,
+    #[scrape(selector = ".star-rating", attr = "class", regex = "star-rating (\\w+)")]
+    rating: String,
+    #[scrape(selector = ".badge")]
+    badge: Option<String>,
+}
+
+// The price without `parse` is left as text, which `f64` rejects
+#[derive(Debug, Scrape)]
+struct Unparsed {
+    #[scrape(selector = "h3 a", attr = "title")]
+    #[allow(dead_code)]
+    title: String,
+    #[scrape(selector = ".price_color")]
+    #[allow(dead_code)]
+    price: f64,
+}
+
+#[test]
+fn rejects_invalid_attributes() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
+
+#[test]
+fn describes_fields_from_attributes() {
+    let fields = Listing::fields();
+    assert_eq!(fields.keys().collect::<Vec<_>>(), ["badge", "price", "rating", "title", "url"]);
+    assert_eq!(fields["price"].kind, FieldType::Number);
+    assert_eq!(fields["url"].kind, FieldType::Url);
+    assert_eq!(fields["rating"].regex.as_deref(), Some("star-rating (\\w+)"));
+    assert!(!fields["badge"].required);
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn extracts_every_record_of_a_listing() {
+    let site = FixtureServer::start("books");
+    let scraper = launch().await;
+    let page = scraper.open(&site.url("catalogue/category/books/poetry_23/index.html")).await.unwrap();
+
+    let listings: Vec<Listing> = scraper.extract_all(&page, ".product_pod").await.unwrap();
+    assert_eq!(
+        listings[..2],
+        [
+            Listing {
+                title: "A Light in the Attic".into(),
+                price: 51.77,
+                url: site.url("catalogue/a-light-in-the-attic_1000/index.html"),
+                rating: "Three".into(),
+                badge: None,
+            },
+            Listing {
+                title: "Olio".into(),
+                price: 23.88,
+                url: site.url("catalogue/olio_984/index.html"),
+                rating: "One".into(),
+                badge: None,
+            },
+        ]
+    );
+
+    let error = scraper.extract_all::<Unparsed>(&page, ".product_pod").await.unwrap_err();
+    assert!(matches!(&error, ScrapeError::Field { field, .. } if field == "price"), "{}", error);
+    assert!(error.to_string().contains("field `price`"), "{}", error);
+    page.close().await.unwrap();
+    scraper.close().await.unwrap();
+}
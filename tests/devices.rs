@@ -0,0 +1,73 @@
+//! Device emulation and contact sheets, against the fixture page in
+//! `tests/fixtures/screenshot`.
+
+mod common;
+
+use common::{launch, FixtureServer};
+use rust_scraper::{contact_sheet_png, Device, DeviceCapture, ScreenshotOptions, WaitFor};
+
+// Width and height from a PNG's header chunk
+fn png_size(image: &[u8]) -> (u32, u32) {
+    assert_eq!(&image[..8], b"\x89PNG\r\n\x1a\n", "not a PNG");
+    let width = u32::from_be_bytes(image[16..20].try_into().unwrap());
+    let height = u32::from_be_bytes(image[20..24].try_into().unwrap());
+    (width, height)
+}
+
+#[test]
+fn finds_presets_by_name_or_slug() {
+    assert_eq!(Device::preset("iphone-15"), Some(Device::iphone_15()));
+    assert_eq!(Device::preset("iPad Air"), Some(Device::ipad_air()));
+    assert_eq!(Device::preset("DESKTOP-1080P"), Some(Device::desktop_1080p()));
+    assert_eq!(Device::preset("nokia"), None);
+    assert_eq!(Device::pixel_8().slug(), "pixel-8");
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn emulates_viewport_user_agent_and_touch() {
+    let site = FixtureServer::start("screenshot");
+    let scraper = launch().await;
+    let page = scraper.browser().new_page("about:blank").await.unwrap();
+    scraper.emulate_device(&page, &Device::iphone_15()).await.unwrap();
+    scraper.goto(&page, &site.url("")).await.unwrap();
+
+    let width: u32 = scraper.evaluate(&page, "innerWidth").await.unwrap();
+    let ratio: f64 = scraper.evaluate(&page, "devicePixelRatio").await.unwrap();
+    let agent: String = scraper.evaluate(&page, "navigator.userAgent").await.unwrap();
+    let touch_points: u32 = scraper.evaluate(&page, "navigator.maxTouchPoints").await.unwrap();
+    assert_eq!((width, ratio), (393, 3.));
+    assert!(agent.contains("iPhone"), "{}", agent);
+    assert!(touch_points > 0);
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn captures_each_device_and_a_contact_sheet() {
+    let site = FixtureServer::start("screenshot");
+    let scraper = launch().await;
+    let dir = std::env::temp_dir().join(format!("rust-scraper-devices-{}", std::process::id()));
+
+    let devices = [Device::iphone_15(), Device::desktop_1080p()];
+    let ready = WaitFor::Visible("#card".to_string());
+    let saved = scraper
+        .take_device_screenshots(&site.url(""), &devices, &dir, &ready, &ScreenshotOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(saved.files, [dir.join("iphone-15.png"), dir.join("desktop-1080p.png")]);
+    assert_eq!(png_size(&std::fs::read(&saved.files[0]).unwrap()), (393 * 3, 852 * 3));
+    assert_eq!(png_size(&std::fs::read(&saved.files[1]).unwrap()), (1920, 1080));
+
+    let (_, height) = png_size(&std::fs::read(&saved.contact_sheet).unwrap());
+    assert!(height > 800);
+    std::fs::remove_dir_all(&dir).unwrap();
+    scraper.close().await.unwrap();
+}
+
+#[test]
+fn contact_sheet_rejects_undecodable_captures() {
+    let capture = DeviceCapture { device: Device::pixel_8(), image: b"not an image".to_vec() };
+    let error = contact_sheet_png(&[capture], 100).unwrap_err();
+    assert_eq!(error.step(), Some("contact sheet"));
+}
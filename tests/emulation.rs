@@ -0,0 +1,62 @@
+//! Per-page emulation, read back through the page's own APIs on the
+//! fixture page in `tests/fixtures/screenshot`.
+
+mod common;
+
+use common::{launch, FixtureServer};
+use rust_scraper::{ColorScheme, Emulation, Geolocation};
+
+#[test]
+fn parses_geolocations_and_color_schemes() {
+    assert_eq!("48.85, 2.35".parse(), Ok(Geolocation::new(48.85, 2.35)));
+    assert_eq!(
+        "48.85,2.35,100".parse(),
+        Ok(Geolocation { latitude: 48.85, longitude: 2.35, accuracy: 100. })
+    );
+    assert!("48.85".parse::<Geolocation>().is_err());
+    assert_eq!("dark".parse(), Ok(ColorScheme::Dark));
+    assert!("sepia".parse::<ColorScheme>().is_err());
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn pages_see_the_emulated_locale_and_place() {
+    let site = FixtureServer::start("screenshot");
+    let scraper = launch().await;
+    let paris = Emulation::default()
+        .locale("fr-FR")
+        .timezone("Europe/Paris")
+        .geolocation(Geolocation::new(48.8566, 2.3522))
+        .color_scheme(ColorScheme::Dark)
+        .reduced_motion();
+    let page = scraper.open_emulated(&site.url(""), &paris).await.unwrap();
+
+    let language: String = scraper.evaluate(&page, "navigator.language").await.unwrap();
+    let timezone: String = scraper.evaluate(&page, "Intl.DateTimeFormat().resolvedOptions().timeZone").await.unwrap();
+    let price: String = scraper.evaluate(&page, "(1234.5).toLocaleString()").await.unwrap();
+    assert_eq!((language.as_str(), timezone.as_str()), ("fr-FR", "Europe/Paris"));
+    assert_eq!(price, "1\u{202f}234,5");
+
+    let media = "[matchMedia('(prefers-color-scheme: dark)').matches, \
+                 matchMedia('(prefers-reduced-motion: reduce)').matches]";
+    assert_eq!(scraper.evaluate::<[bool; 2]>(&page, media).await.unwrap(), [true, true]);
+
+    let position = "new Promise(resolve => navigator.geolocation.getCurrentPosition(\
+                    p => resolve([p.coords.latitude, p.coords.longitude])))";
+    assert_eq!(scraper.evaluate::<[f64; 2]>(&page, position).await.unwrap(), [48.8566, 2.3522]);
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn session_emulation_applies_to_new_tabs() {
+    let site = FixtureServer::start("screenshot");
+    let mut scraper = launch().await;
+    scraper.set_emulation(Some(Emulation::default().user_agent("PriceBot/1.0").timezone("Asia/Tokyo")));
+
+    let page = scraper.open(&site.url("")).await.unwrap();
+    let agent: String = scraper.evaluate(&page, "navigator.userAgent").await.unwrap();
+    let timezone: String = scraper.evaluate(&page, "Intl.DateTimeFormat().resolvedOptions().timeZone").await.unwrap();
+    assert_eq!((agent.as_str(), timezone.as_str()), ("PriceBot/1.0", "Asia/Tokyo"));
+    scraper.close().await.unwrap();
+}
@@ -0,0 +1 @@
+window.tracked = true;
@@ -0,0 +1,165 @@
+//! HAR recording, against the page in `tests/fixtures/capture`, which loads
+//! two JSON documents after the page itself.
+
+mod common;
+
+use common::{launch, FixtureServer};
+use futures::StreamExt;
+use rust_scraper::{Har, HarOptions, PoolOptions, WaitFor};
+
+#[test]
+fn reads_har_files_from_other_tools() {
+    let har: Har = serde_json::from_str(
+        r#"{
+            "log": {
+                "version": "1.2",
+                "creator": { "name": "WebInspector", "version": "537.36" },
+                "entries": [{
+                    "startedDateTime": "2026-10-16T09:30:00.125Z",
+                    "time": 42.5,
+                    "request": {
+                        "method": "GET", "url": "https://shop.example/api?page=2", "httpVersion": "h2",
+                        "cookies": [], "headers": [], "queryString": [{ "name": "page", "value": "2" }],
+                        "headersSize": -1, "bodySize": 0
+                    },
+                    "response": {
+                        "status": 302, "statusText": "", "httpVersion": "h2", "cookies": [],
+                        "headers": [{ "name": "location", "value": "/api?page=3" }],
+                        "content": { "size": 0, "mimeType": "x-unknown" },
+                        "redirectURL": "/api?page=3", "headersSize": -1, "bodySize": -1
+                    },
+                    "cache": {},
+                    "timings": {
+                        "blocked": 1, "dns": -1, "connect": -1, "send": 0.5, "wait": 40, "receive": 1, "ssl": -1
+                    },
+                    "serverIPAddress": "192.0.2.1"
+                }]
+            }
+        }"#,
+    )
+    .unwrap();
+    let entry = &har.log.entries[0];
+    assert!(har.log.pages.is_empty());
+    assert_eq!(entry.request.query_string[0].value, "2");
+    assert_eq!(entry.response.redirect_url, "/api?page=3");
+    assert_eq!(entry.server_ip_address.as_deref(), Some("192.0.2.1"));
+
+    // Fields left out when unset stay out when written back
+    let written = serde_json::to_value(&har).unwrap();
+    assert!(written["log"]["entries"][0].get("_error").is_none());
+    assert_eq!(written["log"]["entries"][0]["response"]["redirectURL"], "/api?page=3");
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn writes_a_page_with_bodies_when_it_closes() {
+    let site = FixtureServer::start("capture");
+    let scraper = launch().await;
+    let page = scraper.browser().new_page("about:blank").await.unwrap();
+    let path = std::env::temp_dir().join(format!("rust-scraper-{}.har", std::process::id()));
+    let recorder = scraper.record_har(&page, &path, &HarOptions { bodies: true }).await.unwrap();
+    scraper.goto(&page, &site.url("")).await.unwrap();
+    scraper.wait(&page, &WaitFor::Predicate("window.stock".to_string())).await.unwrap();
+    assert!(!recorder.har().log.entries.is_empty());
+
+    page.close().await.unwrap();
+    let har = recorder.finished().await.unwrap();
+    let written: Har = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(written, har);
+
+    assert_eq!(har.log.version, "1.2");
+    assert_eq!(har.log.pages.len(), 1);
+    assert_eq!(har.log.pages[0].id, "page_1");
+    assert_eq!(har.log.pages[0].title, site.url(""));
+    assert!(har.log.pages[0].page_timings.on_load.is_some());
+    let entry = |url: &str| har.log.entries.iter().find(|entry| entry.request.url == url).unwrap();
+    let document = entry(&site.url(""));
+    assert_eq!(document.response.status, 200);
+    assert_eq!(document.resource_type.as_deref(), Some("document"));
+    assert!(document.response.content.text.as_deref().unwrap().contains("<ul id=\"products\">"));
+    let stock = entry(&site.url("api/stock.json?ids=1,2"));
+    assert_eq!(stock.request.query_string[0].value, "1,2");
+    assert_eq!(stock.response.content.mime_type, "application/json");
+    assert_eq!(stock.response.content.text.as_deref().map(str::trim), Some(r#"{ "1": 12, "2": 0 }"#));
+    assert!(stock.timings.wait >= 0. && stock.time > 0.);
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn records_every_tab_of_a_session() {
+    let site = FixtureServer::start("capture");
+    let mut scraper = launch().await;
+    let dir = std::env::temp_dir().join(format!("rust-scraper-har-{}", std::process::id()));
+    scraper.set_har_dir(Some(dir.clone()), HarOptions::default());
+
+    scraper.open(&site.url("")).await.unwrap();
+    scraper.open(&site.url("api/products.json")).await.unwrap();
+    scraper.close().await.unwrap();
+
+    let mut files: Vec<String> =
+        std::fs::read_dir(&dir).unwrap().map(|file| file.unwrap().file_name().into_string().unwrap()).collect();
+    files.sort();
+    assert_eq!(files, ["001-127.0.0.1.har", "002-127.0.0.1.har"]);
+    let har: Har = serde_json::from_str(&std::fs::read_to_string(dir.join(&files[1])).unwrap()).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    let entry = har.log.entries.iter().find(|entry| entry.request.url.ends_with("products.json")).unwrap();
+    assert_eq!(entry.response.status, 200);
+    assert_eq!(entry.response.content.text, None);
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn starts_a_page_for_every_document() {
+    let site = FixtureServer::start("capture");
+    let scraper = launch().await;
+    let page = scraper.browser().new_page("about:blank").await.unwrap();
+    let path = std::env::temp_dir().join(format!("rust-scraper-pages-{}.har", std::process::id()));
+    let recorder = scraper.record_har(&page, &path, &HarOptions::default()).await.unwrap();
+    scraper.goto(&page, &site.url("")).await.unwrap();
+    scraper.wait(&page, &WaitFor::Predicate("window.stock".to_string())).await.unwrap();
+    scraper.goto(&page, &site.url("api/products.json")).await.unwrap();
+
+    page.close().await.unwrap();
+    let har = recorder.finished().await.unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let pages: Vec<(&str, &str)> = har.log.pages.iter().map(|page| (page.id.as_str(), page.title.as_str())).collect();
+    let products = site.url("api/products.json");
+    assert_eq!(pages, [("page_1", site.url("").as_str()), ("page_2", products.as_str())]);
+    // The page's own fetch of the same URL belongs to the first page
+    let pagerefs: Vec<&str> = har
+        .log
+        .entries
+        .iter()
+        .filter(|entry| entry.request.url == products)
+        .map(|entry| entry.pageref.as_deref().unwrap())
+        .collect();
+    assert_eq!(pagerefs, ["page_1", "page_2"]);
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn names_pooled_tabs_after_their_first_page() {
+    let site = FixtureServer::start("capture");
+    let mut scraper = launch().await;
+    let dir = std::env::temp_dir().join(format!("rust-scraper-har-pool-{}", std::process::id()));
+    scraper.set_har_dir(Some(dir.clone()), HarOptions::default());
+
+    let pool = scraper.pool(PoolOptions { size: 1, ..PoolOptions::default() });
+    let url = site.url("");
+    let visit = |page| {
+        let (scraper, url) = (&scraper, url.clone());
+        async move { scraper.goto(&page, &url).await.map(drop) }
+    };
+    let visited: Vec<_> = pool.run([visit]).collect().await;
+    visited.into_iter().for_each(Result::unwrap);
+    pool.close().await;
+    scraper.close().await.unwrap();
+
+    let files: Vec<String> =
+        std::fs::read_dir(&dir).unwrap().map(|file| file.unwrap().file_name().into_string().unwrap()).collect();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(files, ["001-127.0.0.1.har"]);
+}
@@ -0,0 +1,116 @@
+//! Request interception, against the fixture page in
+//! `tests/fixtures/intercept`: a page with a stylesheet, an image and a
+//! script. Mocked pages are served from the same fixtures.
+
+mod common;
+
+use common::{launch, FixtureServer};
+use regex::Regex;
+use rust_scraper::{MockResponse, RequestRules, ResourceType, UrlPattern};
+
+#[test]
+fn matches_globs_and_regexes() {
+    let ads = UrlPattern::glob("*://ads.example.com/*");
+    assert!(ads.matches("https://ads.example.com/banner.js"));
+    assert!(!ads.matches("https://example.com/ads.example.com"));
+    assert!(UrlPattern::glob("*.png?v=?").matches("http://x/a.png?v=2"));
+    assert!(!UrlPattern::glob("*.png").matches("http://x/a.png?v=2"));
+
+    let images: UrlPattern = r"/\.(png|jpe?g)$/".parse().unwrap();
+    assert!(images.matches("http://x/photo.jpeg"));
+    assert!(!images.matches("http://x/photo.gif"));
+    assert!("/(/".parse::<UrlPattern>().is_err());
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn blocks_types_and_urls_and_counts_them() {
+    let site = FixtureServer::start("intercept");
+    let scraper = launch().await;
+    let page = scraper.browser().new_page("about:blank").await.unwrap();
+    let rules = RequestRules::default().block_type(ResourceType::Image).block_regex(Regex::new("tracker").unwrap());
+    let interceptor = scraper.intercept(&page, &rules).await.unwrap();
+    scraper.goto(&page, &site.url("")).await.unwrap();
+
+    let loaded: [bool; 2] = scraper.evaluate(&page, "[!!window.imageLoaded, !!window.tracked]").await.unwrap();
+    assert_eq!(loaded, [false, false]);
+    assert!(site.was_requested("/style.css"));
+    assert!(!site.was_requested("/pixel.png") && !site.was_requested("/tracker.js"));
+
+    // The document and stylesheet, and perhaps a favicon
+    let counts = interceptor.counts();
+    assert_eq!(counts.blocked, 2);
+    assert!(counts.allowed >= 2);
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn session_rules_rewrite_headers() {
+    let site = FixtureServer::start("intercept");
+    let mut scraper = launch().await;
+    let rules = RequestRules::default()
+        .set_header("X-Job", "books")
+        .set_header_for(UrlPattern::glob("*.css"), "X-Asset", "style");
+    scraper.set_request_rules(Some(rules));
+
+    let page = scraper.open(&site.url("")).await.unwrap();
+    let requests = site.requests();
+    let document = requests.iter().find(|request| request.target == "/").unwrap();
+    let style = requests.iter().find(|request| request.target == "/style.css").unwrap();
+    assert_eq!(document.header("x-job"), Some("books"));
+    assert_eq!(document.header("x-asset"), None);
+    assert_eq!(style.header("x-asset"), Some("style"));
+
+    let counts = scraper.request_counts(&page).unwrap();
+    assert_eq!(counts.blocked, 0);
+    assert!(counts.allowed >= 4);
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn mocks_answer_without_the_network() {
+    let scraper = launch().await;
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/intercept");
+    let rules = RequestRules::default()
+        .mock(UrlPattern::glob("*/tracker.js"), MockResponse::body("window.tracked = 'mocked';"))
+        .mock(
+            UrlPattern::glob("https://api.example.com/stock"),
+            MockResponse::json(&serde_json::json!({ "stock": 3 })).header("X-Mock", "yes"),
+        )
+        .mock(UrlPattern::glob("*/gone"), MockResponse::body("gone").status(410))
+        .mock_dir("https://shop.example/", &fixtures);
+    let page = scraper.browser().new_page("about:blank").await.unwrap();
+    let interceptor = scraper.intercept(&page, &rules).await.unwrap();
+    let navigation = scraper.goto(&page, "https://shop.example/").await.unwrap();
+    assert_eq!(navigation.status, Some(200));
+
+    let tracked: String = scraper.evaluate(&page, "window.tracked").await.unwrap();
+    let loaded: bool = scraper.evaluate(&page, "!!window.imageLoaded").await.unwrap();
+    assert_eq!((tracked.as_str(), loaded), ("mocked", true));
+
+    let stock = "fetch('https://api.example.com/stock').then(r => r.json()).then(body => body.stock)";
+    assert_eq!(scraper.evaluate::<u32>(&page, stock).await.unwrap(), 3);
+    let statuses = "Promise.all(['/gone', '/missing.css'].map(path => fetch(path).then(r => r.status)))";
+    assert_eq!(scraper.evaluate::<[u16; 2]>(&page, statuses).await.unwrap(), [410, 404]);
+
+    let counts = interceptor.counts();
+    assert!(counts.mocked >= 7, "{:?}", counts);
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn session_mocks_serve_a_fixture_site() {
+    use rust_scraper::sites::books::BooksToScrape;
+
+    let mut scraper = launch().await;
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/books");
+    scraper.set_request_rules(Some(RequestRules::default().mock_dir("https://books.toscrape.com/", fixtures)));
+
+    let categories = BooksToScrape::new(&scraper).categories().await.unwrap();
+    let names: Vec<&str> = categories.iter().map(|category| category.name.as_str()).collect();
+    assert_eq!(names, ["Travel", "Poetry"]);
+    scraper.close().await.unwrap();
+}
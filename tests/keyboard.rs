@@ -0,0 +1,65 @@
+//! Native keyboard input, against the search form in
+//! `tests/fixtures/keyboard`, which shows its field shortly after load and
+//! records every keydown and submission.
+
+mod common;
+
+use common::{launch, FixtureServer};
+use rust_scraper::{Modifiers, ScrapeError, TypeOptions};
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn types_into_a_field_and_submits_with_enter() {
+    let site = FixtureServer::start("keyboard");
+    let scraper = launch().await;
+    let page = scraper.open(&site.url("")).await.unwrap();
+
+    let options = TypeOptions { clear: true, ..TypeOptions::default() };
+    scraper.type_into(&page, "#q", "Rust lang", options).await.unwrap();
+    let focused: bool = scraper.evaluate(&page, "document.activeElement.id === 'q'").await.unwrap();
+    assert!(focused);
+    // No character is lost to the field appearing
+    assert_eq!(scraper.evaluate::<String>(&page, "q.value").await.unwrap(), "Rust lang");
+    assert_eq!(scraper.evaluate::<Option<String>>(&page, "window.submitted").await.unwrap(), None);
+
+    scraper.press(&page, "Enter").await.unwrap();
+    let submitted: Option<String> = scraper.evaluate(&page, "window.submitted").await.unwrap();
+    assert_eq!(submitted.as_deref(), Some("Rust lang"));
+    // Clearing selected the old value with a real shortcut, and every key reached the page's handler
+    let keys: Vec<String> = scraper.evaluate(&page, "window.keys").await.unwrap();
+    let typed: String = keys.iter().filter(|key| key.chars().count() == 1).map(String::as_str).collect();
+    assert!(keys.contains(&"ctrlKey:a".to_string()), "{:?}", keys);
+    assert_eq!(typed, "Rust lang");
+    assert_eq!(keys.last().map(String::as_str), Some("Enter"));
+    page.close().await.unwrap();
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn chords_select_and_shift_letters() {
+    let site = FixtureServer::start("keyboard");
+    let scraper = launch().await;
+    let page = scraper.open(&site.url("")).await.unwrap();
+    scraper.type_into(&page, "#q", "", TypeOptions::default()).await.unwrap();
+
+    let mut keyboard = scraper.keyboard(&page);
+    keyboard.chord("Ctrl+a").await.unwrap();
+    let selected: String =
+        scraper.evaluate(&page, "q.value.slice(q.selectionStart, q.selectionEnd)").await.unwrap();
+    assert_eq!(selected, "old");
+
+    // Typing over the selection, with Shift uppercasing the letter
+    keyboard.chord("Shift+a").await.unwrap();
+    assert_eq!(scraper.evaluate::<String>(&page, "q.value").await.unwrap(), "A");
+    assert_eq!(keyboard.modifiers(), Modifiers::NONE);
+
+    // A key that does not exist releases the ones already down
+    let error = keyboard.chord("Control+Shift+Nope").await.unwrap_err();
+    assert!(matches!(&error, ScrapeError::UnknownKey(key) if key == "Nope"), "{}", error);
+    assert_eq!(keyboard.modifiers(), Modifiers::NONE);
+    keyboard.press("b").await.unwrap();
+    assert_eq!(scraper.evaluate::<String>(&page, "q.value").await.unwrap(), "Ab");
+    page.close().await.unwrap();
+    scraper.close().await.unwrap();
+}
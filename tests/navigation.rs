@@ -0,0 +1,104 @@
+//! Navigation outcomes against `tests/fixtures/navigation`: redirect chains
+//! and error statuses from the fixture server, and the `networkidle` load
+//! states on a page whose fetches the server holds back.
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+use common::{launch, FixtureServer};
+use rust_scraper::{LoadState, Navigation, Redirect, ScrapeError};
+
+#[test]
+fn parses_load_states() {
+    for state in [LoadState::DomContentLoaded, LoadState::Load, LoadState::NetworkIdle0, LoadState::NetworkIdle2] {
+        assert_eq!(state.to_string().parse::<LoadState>(), Ok(state));
+    }
+    assert!("idle".parse::<LoadState>().unwrap_err().contains("networkidle2"));
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn follows_a_redirect_chain() {
+    let site = FixtureServer::start("navigation");
+    site.redirect("/old", "/moved");
+    site.redirect("/moved", "/index.html");
+    let scraper = launch().await;
+    let page = scraper.open("about:blank").await.unwrap();
+
+    let navigation = scraper.goto(&page, &site.url("old")).await.unwrap();
+    assert_eq!(
+        navigation,
+        Navigation {
+            url: site.url("index.html"),
+            status: Some(200),
+            redirects: vec![
+                Redirect { from: site.url("old"), to: site.url("moved"), status: 302 },
+                Redirect { from: site.url("moved"), to: site.url("index.html"), status: 302 },
+            ],
+        }
+    );
+    page.close().await.unwrap();
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn reports_an_error_status_without_failing() {
+    let site = FixtureServer::start("navigation");
+    let scraper = launch().await;
+    let page = scraper.open("about:blank").await.unwrap();
+
+    let navigation = scraper.goto(&page, &site.url("missing.html")).await.unwrap();
+    assert_eq!(navigation, Navigation { url: site.url("missing.html"), status: Some(404), redirects: vec![] });
+    page.close().await.unwrap();
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn waits_for_the_network_to_go_idle() {
+    let site = FixtureServer::start("navigation");
+    site.delay("/first.json", Duration::from_secs(1));
+    site.delay("/second.json", Duration::from_secs(1));
+    let scraper = launch().await;
+
+    let (page, navigation) = scraper.open_until(&site.url("fetches.html"), LoadState::NetworkIdle0).await.unwrap();
+    assert_eq!(navigation.status, Some(200));
+    let finished: Vec<String> = scraper.evaluate(&page, "window.finished.slice().sort()").await.unwrap();
+    assert_eq!(finished, ["first", "second"]);
+    page.close().await.unwrap();
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn networkidle2_tolerates_two_requests_in_flight() {
+    let site = FixtureServer::start("navigation");
+    site.delay("/first.json", Duration::from_secs(5));
+    site.delay("/second.json", Duration::from_secs(5));
+    let scraper = launch().await;
+
+    let started = Instant::now();
+    let (page, _) = scraper.open_until(&site.url("fetches.html"), LoadState::NetworkIdle2).await.unwrap();
+    assert!(started.elapsed() < Duration::from_secs(5), "{:?}", started.elapsed());
+    assert_eq!(scraper.evaluate::<usize>(&page, "window.finished.length").await.unwrap(), 0);
+    page.close().await.unwrap();
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn fails_fast_when_the_document_cannot_be_fetched() {
+    let scraper = launch().await;
+    let page = scraper.open("about:blank").await.unwrap();
+
+    // Nothing listens on port 1, so the connection is refused
+    let unreachable = "http://127.0.0.1:1/";
+    let started = Instant::now();
+    let error = scraper.goto(&page, unreachable).await.unwrap_err();
+    assert!(matches!(&error, ScrapeError::Navigation { url, .. } if url == unreachable), "{}", error);
+    assert!(started.elapsed() < scraper.timeouts().navigation(), "{:?}", started.elapsed());
+    page.close().await.unwrap();
+    scraper.close().await.unwrap();
+}
@@ -0,0 +1,96 @@
+//! Reading listings across pages, against `tests/fixtures/pagination`:
+//! three linked pages whose last "next" link wraps around to the first, a
+//! page whose "next" link redirects back to itself, and numbered pages for
+//! URL templates that run out with a 404 or an empty page.
+
+mod common;
+
+use common::{launch, FixtureServer};
+use rust_scraper::{NextPage, Pagination, PaginationEnd};
+
+#[test]
+fn reads_fifty_pages_unless_limited() {
+    let pagination = Pagination::template("https://example.com/list/{page}").max_pages(3);
+    assert_eq!(pagination.next, NextPage::Template("https://example.com/list/{page}".into()));
+    assert_eq!(pagination.max_pages, 3);
+    assert_eq!(Pagination::link("li.next a").max_pages, 50);
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn stops_when_the_next_link_loops_back() {
+    let site = FixtureServer::start("pagination");
+    let scraper = launch().await;
+    let page = scraper.open("about:blank").await.unwrap();
+
+    let pagination = Pagination::link("li.next a");
+    let listing = scraper.paginate(&page, &site.url("page1.html"), &pagination, "li.item", None).await.unwrap();
+    assert_eq!(listing.items, ["Anchor", "Bell", "Candle", "Drum", "Easel"]);
+    assert_eq!(listing.pages, [site.url("page1.html"), site.url("page2.html"), site.url("page3.html")]);
+    assert_eq!(listing.end, PaginationEnd::Loop { url: site.url("page1.html") });
+    // The first page is not loaded a second time to find out
+    assert_eq!(site.requests().iter().filter(|request| request.target == "/page1.html").count(), 1);
+    page.close().await.unwrap();
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn stops_when_the_next_link_redirects_back() {
+    let site = FixtureServer::start("pagination");
+    site.redirect("/more?page=2", "/more.html");
+    let scraper = launch().await;
+
+    let pagination = Pagination::link("li.next a");
+    let listing = scraper.extract_pages(&site.url("more.html"), &pagination, "li.item", None).await.unwrap();
+    assert_eq!(listing.items, ["Flute"]);
+    assert_eq!(listing.pages, [site.url("more.html")]);
+    assert_eq!(listing.end, PaginationEnd::Loop { url: site.url("more.html") });
+    assert!(site.was_requested("/more?page=2"));
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn stops_a_template_on_a_missing_page() {
+    let site = FixtureServer::start("pagination");
+    let scraper = launch().await;
+
+    let pagination = Pagination::template(site.url("list/{page}.html"));
+    let listing = scraper.extract_pages(&site.url("list/1.html"), &pagination, "li.item", None).await.unwrap();
+    assert_eq!(listing.items, ["Globe", "Harp", "Inkwell"]);
+    assert_eq!(listing.pages, [site.url("list/1.html"), site.url("list/2.html")]);
+    assert_eq!(listing.end, PaginationEnd::LastPage);
+    assert!(site.was_requested("/list/3.html") && !site.was_requested("/list/4.html"));
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn stops_a_template_on_an_empty_page() {
+    let site = FixtureServer::start("pagination");
+    let scraper = launch().await;
+
+    let pagination = Pagination::template(site.url("empty/{page}.html"));
+    let listing = scraper.extract_pages(&site.url("empty/1.html"), &pagination, "li.item", None).await.unwrap();
+    assert_eq!(listing.items, ["Jug"]);
+    assert_eq!(listing.pages, [site.url("empty/1.html")]);
+    assert_eq!(listing.end, PaginationEnd::LastPage);
+    assert!(!site.was_requested("/empty/3.html"));
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn honours_the_page_limit() {
+    let site = FixtureServer::start("pagination");
+    let scraper = launch().await;
+
+    let pagination = Pagination::link("li.next a").max_pages(2);
+    let listing = scraper.extract_pages(&site.url("page1.html"), &pagination, "li.item", None).await.unwrap();
+    assert_eq!(listing.items, ["Anchor", "Bell", "Candle", "Drum"]);
+    assert_eq!(listing.pages.len(), 2);
+    assert_eq!(listing.end, PaginationEnd::PageLimit);
+    assert!(!site.was_requested("/page3.html"));
+    scraper.close().await.unwrap();
+}
@@ -0,0 +1,81 @@
+//! PDF printing, checked against a three-page fixture in `tests/fixtures/pdf`.
+
+mod common;
+
+use common::{launch, FixtureServer};
+use rust_scraper::{Margins, PaperSize, PdfOptions};
+
+// Page objects in a PDF, not counting the `/Pages` tree nodes
+fn page_count(pdf: &[u8]) -> usize {
+    let text = String::from_utf8_lossy(pdf);
+    text.matches("/Type /Page").count() - text.matches("/Type /Pages").count()
+}
+
+// The first page's media box, in points
+fn media_box(pdf: &[u8]) -> String {
+    let text = String::from_utf8_lossy(pdf);
+    let start = text.find("/MediaBox [").expect("a media box") + "/MediaBox [".len();
+    text[start..start + text[start..].find(']').unwrap()].trim().to_string()
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn prints_every_page() {
+    let site = FixtureServer::start("pdf");
+    let scraper = launch().await;
+    let page = scraper.open(&site.url("")).await.unwrap();
+
+    let pdf = scraper.pdf(&page, &PdfOptions::default()).await.unwrap();
+
+    assert!(pdf.starts_with(b"%PDF-"));
+    assert_eq!(page_count(&pdf), 3);
+    assert_eq!(media_box(&pdf), "0 0 612 792");
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn applies_paper_orientation_and_ranges() {
+    let site = FixtureServer::start("pdf");
+    let scraper = launch().await;
+    let page = scraper.open(&site.url("")).await.unwrap();
+
+    let options = PdfOptions::default()
+        .paper(PaperSize::A5)
+        .landscape()
+        .margins(Margins::uniform(0.5))
+        .print_background()
+        .footer_template(r#"<span style="font-size: 8px" class="pageNumber"></span>"#)
+        .page_ranges("2-3");
+    let pdf = scraper.pdf(&page, &options).await.unwrap();
+
+    assert_eq!(page_count(&pdf), 2);
+    // 8.27 x 5.83 inches, at 72 points per inch
+    let size: Vec<f64> = media_box(&pdf).split_whitespace().map(|n| n.parse().unwrap()).collect();
+    assert_eq!(size[2].round(), 595.);
+    assert_eq!(size[3].round(), 420.);
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn print_pdf_writes_a_file() {
+    let site = FixtureServer::start("pdf");
+    let scraper = launch().await;
+    let path = std::env::temp_dir().join(format!("rust-scraper-{}.pdf", std::process::id()));
+
+    scraper.print_pdf(&site.url(""), &path, &PdfOptions::default().page_ranges("1")).await.unwrap();
+
+    let pdf = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(page_count(&pdf), 1);
+    scraper.close().await.unwrap();
+}
+
+#[test]
+fn parses_paper_sizes() {
+    assert_eq!("A4".parse::<PaperSize>(), Ok(PaperSize::A4));
+    assert_eq!("8.5x13".parse::<PaperSize>(), Ok(PaperSize::Custom { width: 8.5, height: 13. }));
+    assert!("b5".parse::<PaperSize>().is_err());
+    assert_eq!(PaperSize::A4.to_string().parse::<PaperSize>(), Ok(PaperSize::A4));
+}
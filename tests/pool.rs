@@ -0,0 +1,147 @@
+//! Tab pooling: how many tasks run at once, when tabs are reused, recycled
+//! and closed, and the order results come back in. Tasks load the page in
+//! `tests/fixtures/pool`, which the server holds back to keep them busy.
+
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use async_std::task;
+use chromiumoxide::Page;
+use common::{launch, FixtureServer};
+use futures::StreamExt;
+use rust_scraper::{PoolOptions, ScrapeError, Scraper};
+
+// Wait for the browser to have `count` tabs open, as closing one is only
+// reflected once Chrome reports the target gone
+async fn expect_tabs(scraper: &Scraper, count: usize) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        let open = scraper.browser().pages().await.unwrap().len();
+        if open == count {
+            return;
+        }
+        assert!(Instant::now() < deadline, "{} tabs open, expected {}", open, count);
+        task::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn runs_at_most_size_tasks_at_once() {
+    let site = FixtureServer::start("pool");
+    site.delay("/", Duration::from_millis(300));
+    let scraper = launch().await;
+    let pool = scraper.pool(PoolOptions { size: 3, ..PoolOptions::default() });
+
+    let (active, peak) = (AtomicUsize::new(0), AtomicUsize::new(0));
+    let (active, peak, site, session) = (&active, &peak, &site, &scraper);
+    let tasks = (0..8).map(|_| {
+        move |page: Page| async move {
+            let running = active.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(running, Ordering::SeqCst);
+            let navigated = session.goto(&page, &site.url("")).await;
+            active.fetch_sub(1, Ordering::SeqCst);
+            navigated.map(|_| ())
+        }
+    });
+    let results: Vec<_> = pool.run(tasks).collect().await;
+    assert_eq!(results.len(), 8);
+    assert!(results.iter().all(Result::is_ok));
+    assert_eq!(peak.load(Ordering::SeqCst), 3);
+    assert_eq!(site.requests().iter().filter(|request| request.target == "/").count(), 8);
+    pool.close().await;
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn reuses_tabs_until_max_uses() {
+    let site = FixtureServer::start("pool");
+    let scraper = launch().await;
+    let before = scraper.browser().pages().await.unwrap().len();
+    let pool = scraper.pool(PoolOptions { size: 1, max_uses: 3 });
+
+    let mut tabs = Vec::new();
+    for _ in 0..7 {
+        let (site, session) = (&site, &scraper);
+        let tab = pool
+            .with_page(|page| async move {
+                // Each task starts on a blank tab, whatever the last one left
+                assert_eq!(session.evaluate::<String>(&page, "location.href").await?, "about:blank");
+                session.goto(&page, &site.url("")).await?;
+                Ok(page.target_id().clone())
+            })
+            .await
+            .unwrap();
+        tabs.push(tab);
+    }
+    assert!(tabs[..3].iter().all(|tab| *tab == tabs[0]));
+    assert!(tabs[3..6].iter().all(|tab| *tab == tabs[3]));
+    assert!(tabs[0] != tabs[3] && tabs[3] != tabs[6] && tabs[0] != tabs[6]);
+    // Recycled tabs are closed, not left behind
+    expect_tabs(&scraper, before + 1).await;
+    pool.close().await;
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn streams_results_as_they_finish() {
+    let scraper = launch().await;
+    let pool = scraper.pool(PoolOptions { size: 3, ..PoolOptions::default() });
+
+    let tasks = [600, 100, 300].into_iter().enumerate().map(|(index, millis)| {
+        move |_page: Page| async move {
+            task::sleep(Duration::from_millis(millis)).await;
+            Ok::<_, ScrapeError>(index)
+        }
+    });
+    let mut results = Box::pin(pool.run(tasks));
+    assert_eq!(results.next().await.unwrap().unwrap(), 1);
+    assert_eq!(results.next().await.unwrap().unwrap(), 2);
+    assert_eq!(results.next().await.unwrap().unwrap(), 0);
+    assert!(results.next().await.is_none());
+    drop(results);
+    pool.close().await;
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn closes_tabs_of_failed_and_abandoned_tasks_and_on_close() {
+    let scraper = launch().await;
+    let before = scraper.browser().pages().await.unwrap().len();
+    let pool = scraper.pool(PoolOptions { size: 2, ..PoolOptions::default() });
+
+    let failed = pool
+        .with_page(|_page| async {
+            Err::<(), _>(ScrapeError::NotFound { step: "task", url: "about:blank".into(), what: "a result".into() })
+        })
+        .await;
+    assert!(matches!(failed, Err(ScrapeError::NotFound { .. })));
+    expect_tabs(&scraper, before).await;
+
+    // Dropping the task mid-way gives its slot back and closes its tab
+    let abandoned = pool.with_page(|_page| async {
+        task::sleep(Duration::from_secs(60)).await;
+        Ok::<_, ScrapeError>(())
+    });
+    assert!(async_std::future::timeout(Duration::from_millis(500), abandoned).await.is_err());
+    expect_tabs(&scraper, before).await;
+
+    // Both slots are free again, and their tabs stay open until the pool closes
+    let tasks = (0..2).map(|_| {
+        |_page: Page| async {
+            task::sleep(Duration::from_millis(200)).await;
+            Ok::<_, ScrapeError>(())
+        }
+    });
+    let results: Vec<_> = pool.run(tasks).collect().await;
+    assert!(results.iter().all(Result::is_ok));
+    expect_tabs(&scraper, before + 2).await;
+    pool.close().await;
+    expect_tabs(&scraper, before).await;
+    scraper.close().await.unwrap();
+}
@@ -0,0 +1,91 @@
+//! Screenshot regions, scales and formats, checked against a fixture page
+//! of known size in `tests/fixtures/screenshot`.
+
+mod common;
+
+use common::{launch, FixtureServer};
+use rust_scraper::{Clip, ImageFormat, ScrapeError, ScreenshotOptions};
+
+// Width and height from a PNG's header chunk
+fn png_size(image: &[u8]) -> (u32, u32) {
+    assert_eq!(&image[..8], b"\x89PNG\r\n\x1a\n", "not a PNG");
+    let width = u32::from_be_bytes(image[16..20].try_into().unwrap());
+    let height = u32::from_be_bytes(image[20..24].try_into().unwrap());
+    (width, height)
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn full_page_covers_the_scroll_height() {
+    let site = FixtureServer::start("screenshot");
+    let scraper = launch().await;
+    let page = scraper.open(&site.url("")).await.unwrap();
+
+    let options = ScreenshotOptions::full_page().device_scale_factor(1.);
+    let (_, height) = png_size(&scraper.screenshot(&page, &options).await.unwrap());
+    assert_eq!(height, 2000);
+
+    let (_, viewport_height) = png_size(&scraper.screenshot(&page, &ScreenshotOptions::default()).await.unwrap());
+    assert!(viewport_height < 2000);
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn element_and_clip_capture_their_box() {
+    let site = FixtureServer::start("screenshot");
+    let scraper = launch().await;
+    let page = scraper.open(&site.url("")).await.unwrap();
+
+    // The card sits below the fold
+    let card = ScreenshotOptions::element("#card").device_scale_factor(1.);
+    assert_eq!(png_size(&scraper.screenshot(&page, &card).await.unwrap()), (200, 100));
+
+    let clip = ScreenshotOptions::clip(Clip { x: 10., y: 20., width: 300., height: 150. }).device_scale_factor(1.);
+    assert_eq!(png_size(&scraper.screenshot(&page, &clip).await.unwrap()), (300, 150));
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn device_scale_factor_multiplies_the_size() {
+    let site = FixtureServer::start("screenshot");
+    let scraper = launch().await;
+    let page = scraper.open(&site.url("")).await.unwrap();
+
+    let options = ScreenshotOptions::element("#card").device_scale_factor(2.);
+    assert_eq!(png_size(&scraper.screenshot(&page, &options).await.unwrap()), (400, 200));
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn encodes_jpeg_and_webp() {
+    let site = FixtureServer::start("screenshot");
+    let scraper = launch().await;
+    let page = scraper.open(&site.url("")).await.unwrap();
+
+    let jpeg = scraper
+        .screenshot(&page, &ScreenshotOptions::default().format(ImageFormat::Jpeg).quality(40))
+        .await
+        .unwrap();
+    assert_eq!(&jpeg[..3], b"\xff\xd8\xff");
+
+    let webp = scraper.screenshot(&page, &ScreenshotOptions::full_page().format(ImageFormat::Webp)).await.unwrap();
+    assert_eq!((&webp[..4], &webp[8..12]), (&b"RIFF"[..], &b"WEBP"[..]));
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn missing_or_hidden_elements_are_not_found() {
+    let site = FixtureServer::start("screenshot");
+    let scraper = launch().await;
+    let page = scraper.open(&site.url("")).await.unwrap();
+
+    for selector in ["#missing", "#hidden"] {
+        let result = scraper.screenshot(&page, &ScreenshotOptions::element(selector)).await;
+        assert!(matches!(result, Err(ScrapeError::NotFound { .. })), "{}: {:?}", selector, result.map(|_| ()));
+    }
+    scraper.close().await.unwrap();
+}
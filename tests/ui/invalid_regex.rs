@@ -0,0 +1,9 @@
+use rust_scraper::Scrape;
+
+#[derive(Scrape)]
+struct Book {
+    #[scrape(selector = ".price_color", regex = "([0-9]+")]
+    price: String,
+}
+
+fn main() {}
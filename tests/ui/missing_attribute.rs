@@ -0,0 +1,10 @@
+use rust_scraper::Scrape;
+
+#[derive(Scrape)]
+struct Book {
+    #[scrape(selector = "h3 a", attr = "title")]
+    title: String,
+    price: f64,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use rust_scraper::Scrape;
+
+#[derive(Scrape)]
+struct Book {
+    #[scrape(selector)]
+    title: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use rust_scraper::Scrape;
+
+#[derive(Scrape)]
+enum Rating {
+    One,
+    Two,
+}
+
+fn main() {}
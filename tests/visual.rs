@@ -0,0 +1,100 @@
+//! Visual regression checks against baselines captured from the pages in
+//! `tests/fixtures/visual`: an 800x600 body with a price box and an ad slot.
+
+mod common;
+
+use std::path::{Path, PathBuf};
+
+use common::{launch, FixtureServer};
+use rust_scraper::{Clip, DiffOptions, ScrapeError, ScreenshotOptions};
+
+// A fresh directory for one test's baseline and diff images
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rust-scraper-visual-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// Width and height from a PNG's header chunk
+fn image_size(path: &Path) -> (u32, u32) {
+    let bytes = std::fs::read(path).unwrap();
+    let width = u32::from_be_bytes(bytes[16..20].try_into().unwrap());
+    let height = u32::from_be_bytes(bytes[20..24].try_into().unwrap());
+    (width, height)
+}
+
+fn body_options() -> DiffOptions {
+    DiffOptions::default().screenshot(ScreenshotOptions::element("body").device_scale_factor(1.))
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn first_capture_becomes_the_baseline() {
+    let site = FixtureServer::start("visual");
+    let scraper = launch().await;
+    let dir = scratch("baseline");
+    let baseline = dir.join("home.png");
+    let page = scraper.open(&site.url("index.html")).await.unwrap();
+
+    let first = scraper.compare_screenshot(&page, &baseline, &body_options()).await.unwrap();
+    assert!(first.new_baseline);
+    assert!(baseline.exists());
+
+    let second = scraper.compare_screenshot(&page, &baseline, &body_options()).await.unwrap();
+    assert!(!second.new_baseline);
+    assert_eq!((second.differing, second.compared), (0, 800 * 600));
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn fails_past_the_threshold_and_writes_a_diff() {
+    let site = FixtureServer::start("visual");
+    let scraper = launch().await;
+    let dir = scratch("threshold");
+    let baseline = dir.join("home.png");
+    let diff_path = dir.join("diff.png");
+    let page = scraper.open(&site.url("index.html")).await.unwrap();
+    scraper.compare_screenshot(&page, &baseline, &body_options()).await.unwrap();
+
+    scraper.goto(&page, &site.url("moved-price.html")).await.unwrap();
+    let options = body_options().diff_path(&diff_path);
+    match scraper.compare_screenshot(&page, &baseline, &options).await {
+        // 40 rows of the 300px box are uncovered, and 40 newly covered
+        Err(ScrapeError::VisualMismatch { percent, diff, .. }) => {
+            assert!((percent - 5.).abs() < 0.1, "{}% differ", percent);
+            assert_eq!(diff, Some(diff_path.clone()));
+        }
+        other => panic!("expected a mismatch, got {:?}", other),
+    }
+    assert_eq!(image_size(&diff_path), (800, 600));
+
+    // A generous threshold lets the same change through
+    let diff = scraper.compare_screenshot(&page, &baseline, &options.threshold(10.)).await.unwrap();
+    assert_eq!(diff.differing, 2 * 40 * 300);
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn masked_regions_are_ignored() {
+    let site = FixtureServer::start("visual");
+    let scraper = launch().await;
+    let dir = scratch("masks");
+    let baseline = dir.join("home.png");
+    let page = scraper.open(&site.url("index.html")).await.unwrap();
+    scraper.compare_screenshot(&page, &baseline, &body_options()).await.unwrap();
+    scraper.goto(&page, &site.url("new-ad.html")).await.unwrap();
+
+    let unmasked = scraper.compare_screenshot(&page, &baseline, &body_options()).await;
+    assert!(matches!(unmasked, Err(ScrapeError::VisualMismatch { .. })));
+
+    let by_selector = scraper.compare_screenshot(&page, &baseline, &body_options().mask("#ad")).await.unwrap();
+    assert_eq!((by_selector.differing, by_selector.compared), (0, 800 * 600 - 300 * 200));
+
+    let rect = Clip { x: 390., y: 290., width: 320., height: 220. };
+    let by_rect = scraper.compare_screenshot(&page, &baseline, &body_options().mask_rect(rect)).await.unwrap();
+    assert_eq!(by_rect.differing, 0);
+    scraper.close().await.unwrap();
+}
@@ -0,0 +1,66 @@
+//! Waiting for page conditions, against `tests/fixtures/wait`, which adds,
+//! shows, removes and retexts elements and pushes a new URL on timers
+//! while a fetch the server holds back is in flight.
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+use common::{launch, FixtureServer};
+use rust_scraper::{ScrapeError, WaitFor, WaitOptions};
+
+#[test]
+fn describes_conditions() {
+    assert_eq!(WaitFor::selector("#items li").to_string(), "`#items li` to exist");
+    assert_eq!(WaitFor::text("#status", "^ready").to_string(), "text of `#status` to match /^ready/");
+    assert_eq!(WaitFor::network_idle(Duration::from_millis(500)).to_string(), "network to be idle for 500ms");
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn resolves_each_condition_as_the_page_changes() {
+    let site = FixtureServer::start("wait");
+    site.delay("/data.json", Duration::from_secs(1));
+    let scraper = launch().await;
+    let page = scraper.open(&site.url("")).await.unwrap();
+
+    scraper.wait(&page, &WaitFor::selector("#items li.item")).await.unwrap();
+    scraper.wait(&page, &WaitFor::visible("#banner")).await.unwrap();
+    scraper.wait(&page, &WaitFor::gone("#spinner")).await.unwrap();
+    scraper.wait(&page, &WaitFor::text("#status", r"^ready: \d+ items$")).await.unwrap();
+    scraper.wait(&page, &WaitFor::url(r"/done\?step=2$")).await.unwrap();
+    assert_eq!(scraper.evaluate::<String>(&page, "location.pathname").await.unwrap(), "/done");
+
+    // The held back response only lands a second after load
+    assert!(!scraper.evaluate::<bool>(&page, "'data' in window").await.unwrap());
+    scraper.wait(&page, &WaitFor::network_idle(Duration::from_millis(300))).await.unwrap();
+    assert_eq!(scraper.evaluate::<i64>(&page, "window.data.items").await.unwrap(), 3);
+
+    scraper.wait(&page, &WaitFor::predicate("window.data && window.data.items === 3")).await.unwrap();
+    page.close().await.unwrap();
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn times_out_on_a_condition_that_never_holds() {
+    let site = FixtureServer::start("wait");
+    let scraper = launch().await;
+    let page = scraper.open(&site.url("")).await.unwrap();
+
+    let timeout = Duration::from_millis(500);
+    let started = Instant::now();
+    let condition = WaitFor::text("#status", "^failed");
+    let error = scraper.wait_with(&page, &condition, WaitOptions::new(timeout)).await.unwrap_err();
+    let elapsed = started.elapsed();
+    match &error {
+        ScrapeError::WaitTimeout { condition, timeout: waited, .. } => {
+            assert_eq!(condition, "text of `#status` to match /^failed/");
+            assert_eq!(*waited, timeout);
+        }
+        other => panic!("expected a wait timeout, got {}", other),
+    }
+    assert!(elapsed >= timeout && elapsed < timeout + Duration::from_secs(1), "{:?}", elapsed);
+    page.close().await.unwrap();
+    scraper.close().await.unwrap();
+}
@@ -0,0 +1,323 @@
+//! Regression suite for the Wikipedia adapter, run against saved copies of
+//! article and search pages in `tests/fixtures/wikipedia`.
+
+mod common;
+
+use common::{launch, FixtureServer};
+use rust_scraper::sites::wikipedia::{DisambiguationOption, Hit, Link, SearchOutcome, Section, Wikipedia};
+
+// The request target (path and query) the adapter asks `site` for
+fn target(site: &FixtureServer, url: &str) -> String {
+    url.strip_prefix(site.url("").trim_end_matches('/')).expect("URL on the fixture server").to_string()
+}
+
+fn outline(sections: &[Section]) -> Vec<(u8, String)> {
+    sections
+        .iter()
+        .flat_map(|section| {
+            std::iter::once((section.level, section.title.clone())).chain(outline(&section.subsections))
+        })
+        .collect()
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn reads_an_article() {
+    let site = FixtureServer::start("wikipedia");
+    let scraper = launch().await;
+    let wiki = Wikipedia::with_base_url(&scraper, &site.url("")).unwrap();
+
+    let article = wiki.article("Rust (programming language)").await.unwrap();
+
+    assert_eq!(article.title, "Rust (programming language)");
+    assert_eq!(article.url, site.url("wiki/Rust_(programming_language)"));
+    assert_eq!(
+        article.lead,
+        "Rust is a general-purpose programming language emphasizing performance, type safety, and \
+         concurrency. It enforces memory safety, meaning that all references point to valid memory.\n\n\
+         Software developer Graydon Hoare created Rust as a personal project while working at Mozilla \
+         Research in 2006."
+    );
+    assert_eq!(
+        article.categories,
+        [
+            "Rust (programming language)",
+            "Concurrent programming languages",
+            "Mozilla",
+            "Programming languages created in 2012",
+        ]
+    );
+    assert_eq!(article.references.len(), 4);
+    assert_eq!(article.references[0], "\"Announcing Rust 1.79.0\". Rust Blog. 2024-06-13. Retrieved 2024-06-13.");
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn reads_infobox_rows_without_footnotes() {
+    let site = FixtureServer::start("wikipedia");
+    let scraper = launch().await;
+    let wiki = Wikipedia::with_base_url(&scraper, &site.url("")).unwrap();
+
+    let article = wiki.article("Rust (programming language)").await.unwrap();
+
+    let rows: Vec<(&str, &str)> =
+        article.infobox.iter().map(|(label, value)| (label.as_str(), value.as_str())).collect();
+    assert_eq!(
+        rows,
+        [
+            ("Paradigms", "Concurrent\nfunctional\ngeneric\nimperative\nstructured"),
+            ("Designed by", "Graydon Hoare"),
+            ("Developer", "The Rust Team"),
+            ("First appeared", "January 19, 2012"),
+            ("Stable release", "1.79.0"),
+            ("Typing discipline", "Affine, inferred, nominal, static, strong"),
+            ("Filename extensions", ".rs, .rlib"),
+            ("Website", "www.rust-lang.org"),
+        ]
+    );
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn nests_sections_by_heading_level() {
+    let site = FixtureServer::start("wikipedia");
+    let scraper = launch().await;
+    let wiki = Wikipedia::with_base_url(&scraper, &site.url("")).unwrap();
+
+    let article = wiki.article("Rust (programming language)").await.unwrap();
+
+    let titles: Vec<&str> = article.sections.iter().map(|section| section.title.as_str()).collect();
+    assert_eq!(titles, ["History", "Syntax and features", "See also", "References"]);
+    assert_eq!(
+        outline(&article.sections),
+        [
+            (2, "History".to_string()),
+            (3, "Origins (2006–2012)".to_string()),
+            (3, "Evolution (2012–2019)".to_string()),
+            (2, "Syntax and features".to_string()),
+            (3, "Memory safety".to_string()),
+            (4, "Ownership and lifetimes".to_string()),
+            (3, "Types".to_string()),
+            (2, "See also".to_string()),
+            (2, "References".to_string()),
+        ]
+    );
+
+    let origins = &article.sections[0].subsections[0];
+    assert_eq!(origins.anchor, "Origins_(2006–2012)");
+    assert_eq!(origins.text, "Rust began as a personal project in 2006 by Mozilla Research employee Graydon Hoare.");
+    assert_eq!(article.sections[0].subsections[1].text.split("\n\n").count(), 2);
+    assert_eq!(article.sections[0].text, "");
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn keeps_only_article_links() {
+    let site = FixtureServer::start("wikipedia");
+    let scraper = launch().await;
+    let wiki = Wikipedia::with_base_url(&scraper, &site.url("")).unwrap();
+
+    let article = wiki.article("Rust (programming language)").await.unwrap();
+
+    let titles: Vec<&str> = article.links.iter().map(|link| link.title.as_str()).collect();
+    assert!(article.links.contains(&Link { title: "Graydon Hoare".into(), url: site.url("wiki/Graydon_Hoare") }));
+    assert!(article.links.contains(&Link { title: "C++".into(), url: site.url("wiki/C%2B%2B") }));
+    assert_eq!(titles.iter().filter(|title| **title == "Memory safety").count(), 1);
+    // Files, categories, citations and navigation boxes are not article links
+    assert!(!titles.iter().any(|title| title.contains(':')));
+    assert!(!titles.contains(&"No Starch Press"));
+    assert!(!titles.contains(&"Go (programming language)"));
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn reads_older_heading_markup() {
+    let site = FixtureServer::start("wikipedia");
+    let scraper = launch().await;
+    let wiki = Wikipedia::with_base_url(&scraper, &site.url("")).unwrap();
+
+    let article = wiki.article("Graydon Hoare").await.unwrap();
+
+    let headings: Vec<(&str, &str)> =
+        article.sections.iter().map(|section| (section.title.as_str(), section.anchor.as_str())).collect();
+    assert_eq!(headings, [("Career", "Career"), ("References", "References")]);
+    assert!(article.sections[0].text.starts_with("Hoare worked at Mozilla"));
+    assert_eq!(article.references, ["Hoare, Graydon (2010-07-07). \"Project Servo\". Mozilla."]);
+    assert_eq!(article.categories, ["Canadian computer programmers", "Mozilla people"]);
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn search_follows_the_redirect_to_an_article() {
+    let site = FixtureServer::start("wikipedia");
+    let scraper = launch().await;
+    let wiki = Wikipedia::with_base_url(&scraper, &site.url("")).unwrap();
+    site.redirect(&target(&site, &wiki.search_url("Graydon Hoare")), "/wiki/Graydon_Hoare");
+
+    let article = match wiki.search("Graydon Hoare").await.unwrap() {
+        SearchOutcome::Article(article) => article,
+        other => panic!("expected an article, got {:?}", other),
+    };
+
+    assert_eq!(article.title, "Graydon Hoare");
+    assert_eq!(article.url, site.url("wiki/Graydon_Hoare"));
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn search_lists_results_with_snippets() {
+    let site = FixtureServer::start("wikipedia");
+    let scraper = launch().await;
+    let wiki = Wikipedia::with_base_url(&scraper, &site.url("")).unwrap();
+    site.route(&target(&site, &wiki.search_url("rust lang")), "search/rust-lang.html");
+
+    let hits = match wiki.search("rust lang").await.unwrap() {
+        SearchOutcome::Results(hits) => hits,
+        other => panic!("expected results, got {:?}", other),
+    };
+
+    let titles: Vec<&str> = hits.iter().map(|hit| hit.title.as_str()).collect();
+    assert_eq!(titles, ["Rust (programming language)", "Graydon Hoare", "Rust (video game)"]);
+    assert_eq!(
+        hits[0],
+        Hit {
+            title: "Rust (programming language)".into(),
+            url: site.url("wiki/Rust_(programming_language)"),
+            snippet: "Rust is a general-purpose programming language emphasizing performance, type safety, and \
+                      concurrency. It enforces memory safety"
+                .into(),
+            size: Some("120 KB".into()),
+            words: Some(12113),
+        }
+    );
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn search_walks_result_pages() {
+    let site = FixtureServer::start("wikipedia");
+    let scraper = launch().await;
+    let wiki = Wikipedia::with_base_url(&scraper, &site.url("")).unwrap().result_pages(3);
+    site.route(&target(&site, &wiki.search_url("rust lang")), "search/rust-lang.html");
+    site.route(
+        "/w/index.php?title=Special:Search&limit=3&offset=3&profile=default&search=rust+lang",
+        "search/rust-lang-2.html",
+    );
+
+    let hits = match wiki.search("rust lang").await.unwrap() {
+        SearchOutcome::Results(hits) => hits,
+        other => panic!("expected results, got {:?}", other),
+    };
+
+    // The second page has no next link, so the walk ends there
+    let sizes: Vec<(&str, Option<&str>, Option<u64>)> =
+        hits.iter().map(|hit| (hit.title.as_str(), hit.size.as_deref(), hit.words)).collect();
+    assert_eq!(
+        sizes,
+        [
+            ("Rust (programming language)", Some("120 KB"), Some(12113)),
+            ("Graydon Hoare", Some("4 KB"), Some(312)),
+            ("Rust (video game)", Some("58 KB"), Some(5201)),
+            ("Rust (disambiguation)", Some("3 KB"), Some(215)),
+            ("Cargo (package manager)", Some("812 bytes"), Some(96)),
+        ]
+    );
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn search_without_matches_lists_no_results() {
+    let site = FixtureServer::start("wikipedia");
+    let scraper = launch().await;
+    let wiki = Wikipedia::with_base_url(&scraper, &site.url("")).unwrap();
+    site.route(&target(&site, &wiki.search_url("qwxzv rustacean")), "search/nothing.html");
+
+    assert_eq!(wiki.search("qwxzv rustacean").await.unwrap(), SearchOutcome::Results(Vec::new()));
+    assert!(wiki.search_article("qwxzv rustacean").await.is_err());
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn search_lists_disambiguation_meanings() {
+    let site = FixtureServer::start("wikipedia");
+    let scraper = launch().await;
+    let wiki = Wikipedia::with_base_url(&scraper, &site.url("")).unwrap();
+    site.redirect(&target(&site, &wiki.search_url("Rust")), "/wiki/Rust_(disambiguation)");
+
+    let options = match wiki.search("Rust").await.unwrap() {
+        SearchOutcome::Disambiguation(options) => options,
+        other => panic!("expected a disambiguation page, got {:?}", other),
+    };
+
+    let option = |title: &str, path: &str, description: &str, section: &str| DisambiguationOption {
+        title: title.into(),
+        url: site.url(path),
+        description: description.into(),
+        section: Some(section.into()),
+    };
+    // Entries without an article link, and links to special pages, are left out
+    assert_eq!(
+        options,
+        [
+            option(
+                "Rust (fungus)",
+                "wiki/Rust_(fungus)",
+                "Rust (fungus), a plant disease caused by fungi of the order Pucciniales",
+                "Science and technology",
+            ),
+            option(
+                "Rust (programming language)",
+                "wiki/Rust_(programming_language)",
+                "Rust (programming language), a general-purpose programming language",
+                "Science and technology",
+            ),
+            option(
+                "Cargo (package manager)",
+                "wiki/Cargo_(package_manager)",
+                "Cargo, its package manager",
+                "Science and technology",
+            ),
+            option(
+                "Rust (video game)",
+                "wiki/Rust_(video_game)",
+                "Rust (video game), a 2013 multiplayer survival game",
+                "Arts and entertainment",
+            ),
+            option(
+                "Rust (2024 film)",
+                "wiki/Rust_(2024_film)",
+                "Rust (2024 film), an American western film",
+                "Arts and entertainment",
+            ),
+            option("Rusty", "wiki/Rusty", "Rusty", "See also"),
+        ]
+    );
+    scraper.close().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs Chrome"]
+async fn search_article_reads_the_best_result() {
+    let site = FixtureServer::start("wikipedia");
+    let scraper = launch().await;
+    let wiki = Wikipedia::with_base_url(&scraper, &site.url("")).unwrap();
+    site.route(&target(&site, &wiki.search_url("rust lang")), "search/rust-lang.html");
+    site.route(&target(&site, &wiki.search_url("graydon hoare")), "search/rust-lang.html");
+
+    // No hit is titled like the term, so the first one is read
+    let article = wiki.search_article("rust lang").await.unwrap();
+    assert_eq!(article.title, "Rust (programming language)");
+
+    let article = wiki.search_article("graydon hoare").await.unwrap();
+    assert_eq!(article.title, "Graydon Hoare");
+    scraper.close().await.unwrap();
+}